@@ -2,29 +2,22 @@ extern crate vnf;
 
 use std::io::Cursor;
 use vnf::column::Type;
-use vnf::column::Value;
-use vnf::VnfWriter;
+use vnf::{row, VnfWriter};
 
 fn main() {
-    let mut writer = VnfWriter::new(&[
-        Type::Integer,
-        Type::Boolean,
-        Type::Char { len: 4 },
-        Type::Boolean,
-    ]);
-    let mut out: Cursor<Vec<u8>> = Cursor::new(vec![]);
-    writer.write_file_header(&mut out).unwrap();
+    let mut writer = VnfWriter::new(
+        Cursor::new(vec![]),
+        &[
+            Type::Integer,
+            Type::Boolean,
+            Type::Char { len: 4 },
+            Type::Boolean,
+        ],
+    );
     writer
-        .write_row(
-            &mut out,
-            &[
-                Value::Integer(4),
-                Value::Boolean(true),
-                Value::Char("Fred"),
-                Value::Null,
-            ],
-        )
+        .write_row(row![4, true, "Fred", None::<bool>])
         .unwrap();
+    let out = writer.finish().unwrap();
 
     assert_eq!(
         &vec![
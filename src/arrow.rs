@@ -0,0 +1,452 @@
+use crate::column::{ConversionError, Type, Value, MAX_VAR_LEN};
+use crate::error::{Error, Result};
+use crate::numeric::Decimal;
+use crate::schema::{Column, Schema};
+use crate::{append_column, VnfWriter, BIT_POSITION, ROW_LENGTH_LEN};
+use ::arrow::array::{Array, AsArray};
+use ::arrow::datatypes::{self as types, DataType, IntervalUnit, TimeUnit};
+use ::arrow::record_batch::RecordBatch;
+use chrono::{TimeZone, Utc};
+use std::convert::TryFrom;
+use std::io::Write;
+
+/// The column type for an Arrow data type.
+///
+/// Timestamps with a time zone are TIMESTAMPTZ, durations are INTERVAL
+/// DAY TO SECOND, and Decimal128 keeps its precision and scale.
+pub fn column_type(data_type: &DataType) -> Result<Type> {
+    Ok(match data_type {
+        DataType::Boolean => Type::Boolean,
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => Type::Integer,
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => Type::Float,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            Type::VarChar { len: MAX_VAR_LEN }
+        }
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+            Type::VarBinary { len: MAX_VAR_LEN }
+        }
+        DataType::FixedSizeBinary(len) if *len > 0 => Type::Binary { len: *len as usize },
+        DataType::Date32 | DataType::Date64 => Type::Date,
+        DataType::Timestamp(_, None) => Type::Timestamp,
+        DataType::Timestamp(_, Some(_)) => Type::TimestampTz,
+        DataType::Time32(_) | DataType::Time64(_) => Type::Time,
+        DataType::Decimal128(precision, scale) if *precision > 0 && *scale >= 0 => Type::Numeric {
+            precision: (*precision).into(),
+            scale: *scale as u32,
+        },
+        DataType::Duration(_) => Type::Interval,
+        DataType::Interval(IntervalUnit::YearMonth) => Type::IntervalYearToMonth,
+        data_type => return Err(Error::UnsupportedType(data_type.to_string())),
+    })
+}
+
+/// A schema with a column for each field of an Arrow schema.
+pub fn schema(schema: &types::Schema) -> Result<Schema> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(Column::new(
+                field.name().as_str(),
+                column_type(field.data_type())?,
+                field.is_nullable(),
+            ))
+        })
+        .collect::<Result<Vec<Column>>>()
+        .map(Schema::new)
+}
+
+impl<W: Write> VnfWriter<W> {
+    /// Write every row of a record batch, whose columns are in schema order.
+    ///
+    /// Each column is encoded in turn, then the encoded values are
+    /// interleaved into rows. Nothing is written if any value fails, and
+    /// the error gives the row's number in the batch.
+    pub fn write_batch(&mut self, batch: &RecordBatch) -> Result<usize> {
+        if batch.num_columns() != self.schema.len() {
+            return Err(Error::Arity {
+                expected: self.schema.len(),
+                actual: batch.num_columns(),
+            });
+        }
+
+        let rows = batch.num_rows();
+        let null_bit_field_len = self.schema.len().div_ceil(8);
+        let mut null_bit_fields = vec![0u8; rows * null_bit_field_len];
+        let mut encoded = Vec::with_capacity(self.schema.len());
+        for (index, (column, array)) in self
+            .schema
+            .columns()
+            .iter()
+            .zip(batch.columns())
+            .enumerate()
+        {
+            let mut data = vec![];
+            let mut ends = Vec::with_capacity(rows);
+            for_each_value(array.as_ref(), |row, value| {
+                let value = value.map_err(|source| Error::Conversion {
+                    index,
+                    name: column.name.clone(),
+                    source,
+                });
+                value
+                    .and_then(|value| {
+                        if let Value::Null = value {
+                            null_bit_fields[row * null_bit_field_len + index / 8] |=
+                                BIT_POSITION[index % 8];
+                        }
+                        append_column(&mut data, index, column, &value)
+                    })
+                    .map_err(|source| Error::Record {
+                        record: row as u64 + 1,
+                        source: Box::new(source),
+                    })?;
+                ends.push(data.len());
+                Ok(())
+            })?;
+            encoded.push((data, ends));
+        }
+
+        self.buffer.clear();
+        for row in 0..rows {
+            let row_start = self.buffer.len();
+            self.buffer.extend_from_slice(&[0; ROW_LENGTH_LEN]);
+            self.buffer.extend_from_slice(
+                &null_bit_fields[row * null_bit_field_len..(row + 1) * null_bit_field_len],
+            );
+            let data_start = self.buffer.len();
+            for (data, ends) in &encoded {
+                let start = if row == 0 { 0 } else { ends[row - 1] };
+                self.buffer.extend_from_slice(&data[start..ends[row]]);
+            }
+            let data_len = (self.buffer.len() - data_start) as u32;
+            self.buffer[row_start..data_start - null_bit_field_len]
+                .copy_from_slice(&data_len.to_le_bytes());
+        }
+        self.write_buffer()
+    }
+}
+
+/// Call `f` with the index and value of each element of an array, NULL for
+/// null elements.
+fn for_each_value<'a, F>(array: &'a dyn Array, mut f: F) -> Result<()>
+where
+    F: FnMut(usize, std::result::Result<Value<'a>, ConversionError>) -> Result<()>,
+{
+    macro_rules! each {
+        ($array:expr, |$a:ident, $i:ident| $value:expr) => {{
+            let $a = $array;
+            for $i in 0..$a.len() {
+                if $a.is_null($i) {
+                    f($i, Ok(Value::Null))?;
+                } else {
+                    f($i, $value)?;
+                }
+            }
+        }};
+    }
+    // Primitive arrays whose values convert straight to a `Value`
+    macro_rules! primitive {
+        ($type:ty) => {
+            each!(array.as_primitive::<$type>(), |a, i| Ok(a.value(i).into()))
+        };
+    }
+    // Temporal arrays converted through chrono, which fails out of range
+    macro_rules! temporal {
+        ($type:ty, $as_chrono:ident, $to_value:expr) => {
+            each!(array.as_primitive::<$type>(), |a, i| a
+                .$as_chrono(i)
+                .map($to_value)
+                .ok_or_else(|| ConversionError::InvalidConversion {
+                    column_type: array.data_type().to_string(),
+                    value: a.value(i).to_string(),
+                }))
+        };
+    }
+
+    // Arrow timestamps are UTC, with any time zone only for display
+    let timestamp_tz = |timestamp| Value::from(Utc.from_utc_datetime(&timestamp));
+
+    match array.data_type() {
+        DataType::Boolean => each!(array.as_boolean(), |a, i| Ok(Value::Boolean(a.value(i)))),
+        DataType::Int8 => primitive!(types::Int8Type),
+        DataType::Int16 => primitive!(types::Int16Type),
+        DataType::Int32 => primitive!(types::Int32Type),
+        DataType::Int64 => primitive!(types::Int64Type),
+        DataType::UInt8 => primitive!(types::UInt8Type),
+        DataType::UInt16 => primitive!(types::UInt16Type),
+        DataType::UInt32 => primitive!(types::UInt32Type),
+        DataType::UInt64 => each!(array.as_primitive::<types::UInt64Type>(), |a, i| {
+            Value::try_from(a.value(i))
+        }),
+        DataType::Float16 => each!(array.as_primitive::<types::Float16Type>(), |a, i| Ok(
+            Value::Float(a.value(i).to_f64())
+        )),
+        DataType::Float32 => primitive!(types::Float32Type),
+        DataType::Float64 => primitive!(types::Float64Type),
+        DataType::Utf8 => each!(array.as_string::<i32>(), |a, i| Ok(a.value(i).into())),
+        DataType::LargeUtf8 => each!(array.as_string::<i64>(), |a, i| Ok(a.value(i).into())),
+        DataType::Utf8View => each!(array.as_string_view(), |a, i| Ok(a.value(i).into())),
+        DataType::Binary => each!(array.as_binary::<i32>(), |a, i| Ok(a.value(i).into())),
+        DataType::LargeBinary => each!(array.as_binary::<i64>(), |a, i| Ok(a.value(i).into())),
+        DataType::BinaryView => each!(array.as_binary_view(), |a, i| Ok(a.value(i).into())),
+        DataType::FixedSizeBinary(_) => {
+            each!(array.as_fixed_size_binary(), |a, i| Ok(a.value(i).into()))
+        }
+        DataType::Date32 => temporal!(types::Date32Type, value_as_date, Value::Date),
+        DataType::Date64 => temporal!(types::Date64Type, value_as_date, Value::Date),
+        DataType::Timestamp(TimeUnit::Second, None) => {
+            temporal!(
+                types::TimestampSecondType,
+                value_as_datetime,
+                Value::Timestamp
+            )
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            temporal!(
+                types::TimestampMillisecondType,
+                value_as_datetime,
+                Value::Timestamp
+            )
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            temporal!(
+                types::TimestampMicrosecondType,
+                value_as_datetime,
+                Value::Timestamp
+            )
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            temporal!(
+                types::TimestampNanosecondType,
+                value_as_datetime,
+                Value::Timestamp
+            )
+        }
+        DataType::Timestamp(TimeUnit::Second, Some(_)) => {
+            temporal!(types::TimestampSecondType, value_as_datetime, timestamp_tz)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, Some(_)) => {
+            temporal!(
+                types::TimestampMillisecondType,
+                value_as_datetime,
+                timestamp_tz
+            )
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, Some(_)) => {
+            temporal!(
+                types::TimestampMicrosecondType,
+                value_as_datetime,
+                timestamp_tz
+            )
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, Some(_)) => {
+            temporal!(
+                types::TimestampNanosecondType,
+                value_as_datetime,
+                timestamp_tz
+            )
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            temporal!(types::Time32SecondType, value_as_time, Value::Time)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            temporal!(types::Time32MillisecondType, value_as_time, Value::Time)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            temporal!(types::Time64MicrosecondType, value_as_time, Value::Time)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            temporal!(types::Time64NanosecondType, value_as_time, Value::Time)
+        }
+        DataType::Decimal128(_, scale) if *scale >= 0 => {
+            each!(array.as_primitive::<types::Decimal128Type>(), |a, i| Ok(
+                Value::Numeric(Decimal::new(a.value(i), *scale as u32))
+            ))
+        }
+        DataType::Duration(TimeUnit::Second) => {
+            temporal!(
+                types::DurationSecondType,
+                value_as_duration,
+                Value::Interval
+            )
+        }
+        DataType::Duration(TimeUnit::Millisecond) => {
+            temporal!(
+                types::DurationMillisecondType,
+                value_as_duration,
+                Value::Interval
+            )
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            temporal!(
+                types::DurationMicrosecondType,
+                value_as_duration,
+                Value::Interval
+            )
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            temporal!(
+                types::DurationNanosecondType,
+                value_as_duration,
+                Value::Interval
+            )
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => each!(
+            array.as_primitive::<types::IntervalYearMonthType>(),
+            |a, i| Ok(Value::IntervalYearToMonth(a.value(i).into()))
+        ),
+        data_type => return Err(Error::UnsupportedType(data_type.to_string())),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row;
+    use ::arrow::array::{
+        ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Int64Array, StringArray,
+        TimestampMicrosecondArray, UInt64Array,
+    };
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec![Some("one"), None, Some("three")])),
+            Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+            Arc::new(Date32Array::from(vec![Some(10_599), None, Some(0)])),
+            Arc::new(
+                TimestampMicrosecondArray::from(vec![Some(915_793_477_000_000), None, None])
+                    .with_timezone("+05:00"),
+            ),
+            Arc::new(
+                Decimal128Array::from(vec![Some(12_345), None, Some(-1)])
+                    .with_precision_and_scale(18, 4)
+                    .unwrap(),
+            ),
+            Arc::new(BinaryArray::from(vec![
+                Some(&b"\xab\xcd"[..]),
+                None,
+                Some(&b""[..]),
+            ])),
+        ];
+        RecordBatch::try_from_iter(
+            ["id", "name", "active", "joined", "seen", "balance", "raw"]
+                .iter()
+                .zip(columns),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn schema_from_arrow() {
+        let schema = schema(&batch().schema()).unwrap();
+        assert_eq!(
+            vec![
+                Type::Integer,
+                Type::VarChar { len: MAX_VAR_LEN },
+                Type::Boolean,
+                Type::Date,
+                Type::TimestampTz,
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4
+                },
+                Type::VarBinary { len: MAX_VAR_LEN }
+            ],
+            schema.column_types()
+        );
+        assert_eq!(Some(6), schema.index_of("raw"));
+        assert!(matches!(
+            column_type(&DataType::Null),
+            Err(Error::UnsupportedType(_))
+        ));
+    }
+
+    #[test]
+    fn write_batch() {
+        let batch = batch();
+        let schema = schema(&batch.schema()).unwrap();
+        let mut writer = VnfWriter::new(vec![], schema.clone());
+        writer.write_batch(&batch).unwrap();
+
+        let joined = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let seen = Utc.from_utc_datetime(&joined.and_hms_opt(11, 4, 37).unwrap());
+        let mut expected = VnfWriter::new(vec![], schema);
+        for row in &[
+            row![
+                1,
+                "one",
+                true,
+                joined,
+                seen,
+                Decimal::new(12_345, 4),
+                &[0xab_u8, 0xcd]
+            ],
+            row![
+                2,
+                Value::Null,
+                false,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null
+            ],
+            row![
+                3,
+                "three",
+                Value::Null,
+                NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+                Value::Null,
+                Decimal::new(-1, 4),
+                &[] as &[u8]
+            ],
+        ] {
+            expected.write_row(*row).unwrap();
+        }
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn write_batch_errors() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "id",
+            Arc::new(UInt64Array::from(vec![Some(1), None, Some(u64::MAX)])) as ArrayRef,
+        )])
+        .unwrap();
+        let mut writer = VnfWriter::new(
+            vec![],
+            Schema::new(vec![Column::new("id", Type::Integer, false)]),
+        );
+        assert!(matches!(
+            writer.write_batch(&batch),
+            Err(Error::Record { record: 2, ref source }) if matches!(**source, Error::NotNull { .. })
+        ));
+
+        let mut writer = VnfWriter::new(vec![], &[Type::Integer]);
+        assert!(matches!(
+            writer.write_batch(&batch),
+            Err(Error::Record { record: 3, ref source }) if matches!(**source, Error::Conversion { .. })
+        ));
+        assert_eq!(0, writer.bytes_written());
+
+        let mut writer = VnfWriter::new(vec![], &[Type::Integer, Type::Integer]);
+        assert!(matches!(
+            writer.write_batch(&batch),
+            Err(Error::Arity {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+}
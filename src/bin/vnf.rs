@@ -0,0 +1,383 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use vnf::column::Value;
+use vnf::json::BinaryEncoding;
+use vnf::{CreateTable, CsvConverter, JsonConverter, Schema, TextFormat, VnfReader, VnfWriter};
+
+/// Prepare and debug Vertica Native Format files.
+///
+/// Schema files have one `name TYPE [NOT NULL]` line per column, such as the
+/// column list of a CREATE TABLE statement.
+#[derive(Parser)]
+#[command(name = "vnf", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert records to a VNF file
+    Convert(Convert),
+    /// Print the header and rows of a VNF file
+    Inspect(Inspect),
+    /// Check every row of a VNF file can be read
+    Validate(Validate),
+    /// Print the CREATE TABLE statement for a schema
+    Ddl(Ddl),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    /// JSON Lines, one object per line
+    Json,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+#[derive(Args)]
+struct Convert {
+    /// Schema file, inferred from Parquet input when omitted
+    #[arg(short, long)]
+    schema: Option<PathBuf>,
+    /// Input file, `-` for standard input
+    input: PathBuf,
+    /// Output file, `-` for standard output
+    output: PathBuf,
+    #[arg(short, long, value_enum, default_value = "csv")]
+    format: Format,
+    /// CSV field delimiter
+    #[arg(long, default_value = ",")]
+    delimiter: char,
+    /// The CSV input has no header record
+    #[arg(long)]
+    no_header: bool,
+    /// Text that loads as NULL, may be repeated
+    #[arg(long = "null", value_name = "TEXT")]
+    null_values: Vec<String>,
+    /// Read a JSON column from a JSON pointer, may be repeated
+    #[arg(long = "path", value_name = "COLUMN=POINTER")]
+    paths: Vec<String>,
+    /// JSON binary values are hex rather than base64
+    #[arg(long)]
+    hex: bool,
+    /// strftime format for DATE columns
+    #[arg(long)]
+    date_format: Option<String>,
+    /// strftime format for TIMESTAMP columns
+    #[arg(long)]
+    timestamp_format: Option<String>,
+    /// Write rejected records to this file
+    #[arg(long, value_name = "FILE")]
+    rejected_data: Option<PathBuf>,
+    /// Write the reason for each rejected record to this file
+    #[arg(long, value_name = "FILE")]
+    exceptions: Option<PathBuf>,
+    /// Fail on the first record that does not convert
+    #[arg(long)]
+    abort_on_error: bool,
+}
+
+#[derive(Args)]
+struct Inspect {
+    /// VNF file, `-` for standard input
+    input: PathBuf,
+    /// Schema file, to decode row data as values
+    #[arg(short, long)]
+    schema: Option<PathBuf>,
+    /// Print at most this many rows
+    #[arg(short = 'n', long)]
+    limit: Option<usize>,
+}
+
+#[derive(Args)]
+struct Validate {
+    /// VNF file, `-` for standard input
+    input: PathBuf,
+    /// Schema file, to also check the column types and decode every value
+    #[arg(short, long)]
+    schema: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct Ddl {
+    /// Schema file
+    #[arg(short, long)]
+    schema: PathBuf,
+    /// Table name, optionally qualified with a schema name
+    table: String,
+    #[arg(long)]
+    if_not_exists: bool,
+    /// Sort order of the super projection, comma separated
+    #[arg(long, value_delimiter = ',', value_name = "COLUMNS")]
+    order_by: Vec<String>,
+    /// Segment by a hash of these columns, comma separated
+    #[arg(long, value_delimiter = ',', value_name = "COLUMNS")]
+    segmented_by: Vec<String>,
+    /// Replicate the table on all nodes
+    #[arg(long, conflicts_with = "segmented_by")]
+    unsegmented: bool,
+}
+
+fn main() {
+    let result = match Cli::parse().command {
+        Command::Convert(args) => convert(args),
+        Command::Inspect(args) => inspect(args),
+        Command::Validate(args) => validate(args),
+        Command::Ddl(args) => ddl(args),
+    };
+    if let Err(e) = result {
+        eprintln!("vnf: {}", e);
+        process::exit(1);
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn read_schema(path: &Path) -> Result<Schema> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    text.parse()
+        .map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+fn open(path: &Path) -> Result<Box<dyn Read>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin()));
+    }
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+fn create(path: &Path) -> Result<Box<dyn Write>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdout()));
+    }
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(Box::new(BufWriter::new(file)))
+}
+
+fn convert(args: Convert) -> Result<()> {
+    let schema = args.schema.as_deref().map(read_schema).transpose()?;
+    let mut format = TextFormat::default();
+    if !args.null_values.is_empty() {
+        format.null_values = args.null_values.clone();
+    }
+    if let Some(date_format) = &args.date_format {
+        format = format.date_format(date_format);
+    }
+    if let Some(timestamp_format) = &args.timestamp_format {
+        format = format.timestamp_format(timestamp_format);
+    }
+
+    let summary = match args.format {
+        Format::Csv => {
+            let schema = schema.ok_or("--schema is required for CSV input")?;
+            let mut writer = VnfWriter::new(create(&args.output)?, schema);
+            let mut converter = CsvConverter::new().format(format);
+            if let Some(path) = &args.rejected_data {
+                converter = converter.rejected_data(create(path)?);
+            }
+            if let Some(path) = &args.exceptions {
+                converter = converter.exceptions(create(path)?);
+            }
+            if args.abort_on_error {
+                converter = converter.abort_on_error();
+            }
+            let delimiter = u8::try_from(args.delimiter)
+                .map_err(|_| "the delimiter must be a single byte character")?;
+            let summary = converter.convert(
+                &mut csv::ReaderBuilder::new()
+                    .delimiter(delimiter)
+                    .has_headers(!args.no_header)
+                    .from_reader(open(&args.input)?),
+                &mut writer,
+            )?;
+            writer.finish()?;
+            summary
+        }
+        Format::Json => {
+            let schema = schema.ok_or("--schema is required for JSON input")?;
+            let mut writer = VnfWriter::new(create(&args.output)?, schema);
+            let mut converter = JsonConverter::new().format(format);
+            for path in &args.paths {
+                let (column, pointer) = path
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected COLUMN=POINTER, found `{}`", path))?;
+                converter = converter.path(column, pointer);
+            }
+            if args.hex {
+                converter = converter.binary_encoding(BinaryEncoding::Hex);
+            }
+            if let Some(path) = &args.rejected_data {
+                converter = converter.rejected_data(create(path)?);
+            }
+            if let Some(path) = &args.exceptions {
+                converter = converter.exceptions(create(path)?);
+            }
+            if args.abort_on_error {
+                converter = converter.abort_on_error();
+            }
+            let summary = converter.convert(BufReader::new(open(&args.input)?), &mut writer)?;
+            writer.finish()?;
+            summary
+        }
+        #[cfg(feature = "parquet")]
+        Format::Parquet => {
+            if args.input == Path::new("-") {
+                return Err("Parquet input must be a file".into());
+            }
+            if args.rejected_data.is_some() || args.exceptions.is_some() || args.abort_on_error {
+                return Err("Parquet input is never rejected, any error is fatal".into());
+            }
+            let file =
+                File::open(&args.input).map_err(|e| format!("{}: {}", args.input.display(), e))?;
+            let converter = vnf::ParquetConverter::new(file)?;
+            let schema = match schema {
+                Some(schema) => schema,
+                None => converter.schema()?,
+            };
+            let mut writer = VnfWriter::new(create(&args.output)?, schema);
+            let rows = converter.convert(&mut writer)?;
+            writer.finish()?;
+            vnf::Summary { rows, rejected: 0 }
+        }
+    };
+    eprintln!(
+        "{} rows loaded, {} rejected",
+        summary.rows, summary.rejected
+    );
+    Ok(())
+}
+
+fn inspect(args: Inspect) -> Result<()> {
+    let schema = args.schema.as_deref().map(read_schema).transpose()?;
+    let reader = VnfReader::new(open(&args.input)?)?;
+    if let Some(schema) = &schema {
+        reader.check_schema(schema)?;
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    writeln!(out, "{} columns", reader.column_widths().len())?;
+    for (index, width) in reader.column_widths().iter().enumerate() {
+        let width = match width {
+            &u32::MAX => String::from("variable"),
+            width => format!("{} bytes", width),
+        };
+        match &schema {
+            Some(schema) => {
+                let column = &schema.columns()[index];
+                writeln!(
+                    out,
+                    "  #{} {} {} ({})",
+                    index, column.name, column.column_type, width
+                )?;
+            }
+            None => writeln!(out, "  #{} ({})", index, width)?,
+        }
+    }
+
+    for (number, row) in reader.take(args.limit.unwrap_or(usize::MAX)).enumerate() {
+        let row = row?;
+        write!(out, "row {}: ", number)?;
+        match &schema {
+            Some(schema) => {
+                let values = row.values(schema)?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(out, ", ")?;
+                    }
+                    write!(out, "{}={}", schema.columns()[index].name, display(value))?;
+                }
+                writeln!(out)?;
+            }
+            None => writeln!(
+                out,
+                "null bits {}, data {}",
+                hex(row.null_bit_field()),
+                hex(row.data())
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn validate(args: Validate) -> Result<()> {
+    let schema = args.schema.as_deref().map(read_schema).transpose()?;
+    let reader = VnfReader::new(open(&args.input)?)?;
+    if let Some(schema) = &schema {
+        reader.check_schema(schema)?;
+    }
+    let mut rows = 0u64;
+    for row in reader {
+        let row = row.map_err(|e| format!("row {}: {}", rows, e))?;
+        if let Some(schema) = &schema {
+            row.values(schema)
+                .map_err(|e| format!("row {}: {}", rows, e))?;
+        }
+        rows += 1;
+    }
+    println!("{} rows OK", rows);
+    Ok(())
+}
+
+fn ddl(args: Ddl) -> Result<()> {
+    let schema = read_schema(&args.schema)?;
+    let order_by: Vec<&str> = args.order_by.iter().map(String::as_str).collect();
+    let segmented_by: Vec<&str> = args.segmented_by.iter().map(String::as_str).collect();
+    for name in order_by.iter().chain(&segmented_by) {
+        if schema.index_of(name).is_none() {
+            return Err(format!("no column named `{}` in the schema", name).into());
+        }
+    }
+
+    let mut create_table = CreateTable::new(&args.table, &schema);
+    if args.if_not_exists {
+        create_table = create_table.if_not_exists();
+    }
+    if !order_by.is_empty() {
+        create_table = create_table.order_by(&order_by);
+    }
+    if !segmented_by.is_empty() {
+        create_table = create_table.segmented_by_hash(&segmented_by);
+    }
+    if args.unsegmented {
+        create_table = create_table.unsegmented();
+    }
+    println!("{};", create_table);
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A value as it would be written in SQL.
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("NULL"),
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Char(s) | Value::VarChar(s) => vnf::copy::quote_literal(s),
+        Value::Binary(b) => format!("x'{}'", hex(b)),
+        Value::Date(d) => format!("'{}'", d),
+        Value::Timestamp(t) => format!("'{}'", t),
+        Value::TimestampTz(t) => format!("'{}'", t),
+        Value::Time(t) => format!("'{}'", t),
+        Value::TimeTz(t, offset) => format!("'{}{}'", t, offset),
+        Value::Numeric(d) => d.to_string(),
+        Value::Interval(d) => format!("'{}'", d),
+        Value::IntervalYearToMonth(months) => format!(
+            "'{}{}-{}'",
+            if *months < 0 { "-" } else { "" },
+            months.unsigned_abs() / 12,
+            months.unsigned_abs() % 12
+        ),
+    }
+}
@@ -1,28 +1,210 @@
+use crate::date::{self, VerticaDate, VerticaTime};
+use crate::numeric::{self, Decimal};
+use chrono::{
+    DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+};
+use std::convert::TryFrom;
 use std::fmt;
-use std::u32;
+use std::str::FromStr;
 use thiserror::Error;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Type {
     Boolean,
     Integer,
     Float,
     Char { len: usize },
-    VarChar,
+    VarChar { len: usize },
     Date,
     Timestamp,
     TimestampTz,
     Time,
     TimeTz,
-    VarBinary,
+    VarBinary { len: usize },
     Binary { len: usize },
-    Numeric { precision: u32, _scale: u32 },
+    Numeric { precision: u32, scale: u32 },
     Interval,
+    IntervalYearToMonth,
 }
 
+/// Longest CHAR, VARCHAR, BINARY and VARBINARY Vertica allows, and the
+/// length of VARCHAR and VARBINARY when none is declared.
+pub const MAX_VAR_LEN: usize = 65_000;
+
+/// Longest LONG VARCHAR and LONG VARBINARY Vertica allows.
+pub const MAX_LONG_VAR_LEN: usize = 32_000_000;
+
+/// Length of LONG VARCHAR and LONG VARBINARY when none is declared.
+const DEFAULT_LONG_VAR_LEN: usize = 1_048_576;
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Type::Boolean => write!(f, "BOOLEAN"),
+            Type::Integer => write!(f, "INTEGER"),
+            Type::Float => write!(f, "FLOAT"),
+            Type::Char { len } => write!(f, "CHAR({})", len),
+            Type::VarChar { len } if *len > MAX_VAR_LEN => write!(f, "LONG VARCHAR({})", len),
+            Type::VarChar { len } => write!(f, "VARCHAR({})", len),
+            Type::Date => write!(f, "DATE"),
+            Type::Timestamp => write!(f, "TIMESTAMP"),
+            Type::TimestampTz => write!(f, "TIMESTAMPTZ"),
+            Type::Time => write!(f, "TIME"),
+            Type::TimeTz => write!(f, "TIMETZ"),
+            Type::VarBinary { len } if *len > MAX_VAR_LEN => {
+                write!(f, "LONG VARBINARY({})", len)
+            }
+            Type::VarBinary { len } => write!(f, "VARBINARY({})", len),
+            Type::Binary { len } => write!(f, "BINARY({})", len),
+            Type::Numeric { precision, scale } => write!(f, "NUMERIC({},{})", precision, scale),
+            Type::Interval => write!(f, "INTERVAL DAY TO SECOND"),
+            Type::IntervalYearToMonth => write!(f, "INTERVAL YEAR TO MONTH"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseTypeError {
+    #[error("unknown type {0:?}")]
+    Unknown(String),
+    #[error("invalid arguments for type {0:?}")]
+    InvalidArguments(String),
+}
+
+/// Parse a Vertica SQL type name, such as `NUMERIC(18,4)` or
+/// `TIMESTAMP WITH TIME ZONE`, including Vertica's synonyms. VARCHAR and
+/// VARBINARY without a length are 65000 long rather than Vertica's 80, and
+/// the precision of times and intervals is accepted but not kept.
+impl FromStr for Type {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.to_uppercase();
+        let (name, args, rest) = match (upper.find('('), upper.find(')')) {
+            (Some(open), Some(close)) if open < close => (
+                &upper[..open],
+                Some(&upper[open + 1..close]),
+                &upper[close + 1..],
+            ),
+            (None, None) => (upper.as_str(), None, ""),
+            _ => return Err(ParseTypeError::InvalidArguments(s.to_string())),
+        };
+        let name = name
+            .split_whitespace()
+            .chain(rest.split_whitespace())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let args = match args {
+            Some(args) => args
+                .split(',')
+                .map(|arg| arg.trim().parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(|_| ParseTypeError::InvalidArguments(s.to_string()))?,
+            None => vec![],
+        };
+        let invalid_arguments = || ParseTypeError::InvalidArguments(s.to_string());
+        let at_most = |n: usize| {
+            if args.len() <= n {
+                Ok(())
+            } else {
+                Err(invalid_arguments())
+            }
+        };
+        let len = |default: usize, max: usize| -> Result<usize, ParseTypeError> {
+            at_most(1)?;
+            match args.first() {
+                None => Ok(default),
+                Some(&len) if len > 0 && len as usize <= max => Ok(len as usize),
+                Some(_) => Err(invalid_arguments()),
+            }
+        };
+
+        let column_type = match name.as_str() {
+            "BOOLEAN" | "BOOL" => {
+                at_most(0)?;
+                Type::Boolean
+            }
+            "INTEGER" | "INT" | "BIGINT" | "INT8" | "SMALLINT" | "TINYINT" => {
+                at_most(0)?;
+                Type::Integer
+            }
+            "FLOAT" | "FLOAT8" | "REAL" | "DOUBLE PRECISION" => {
+                at_most(1)?;
+                Type::Float
+            }
+            "CHAR" | "CHARACTER" => Type::Char {
+                len: len(1, MAX_VAR_LEN)?,
+            },
+            "VARCHAR" | "CHARACTER VARYING" => Type::VarChar {
+                len: len(MAX_VAR_LEN, MAX_VAR_LEN)?,
+            },
+            "LONG VARCHAR" => Type::VarChar {
+                len: len(DEFAULT_LONG_VAR_LEN, MAX_LONG_VAR_LEN)?,
+            },
+            "BINARY" => Type::Binary {
+                len: len(1, MAX_VAR_LEN)?,
+            },
+            "VARBINARY" | "BINARY VARYING" | "BYTEA" | "RAW" => Type::VarBinary {
+                len: len(MAX_VAR_LEN, MAX_VAR_LEN)?,
+            },
+            "LONG VARBINARY" => Type::VarBinary {
+                len: len(DEFAULT_LONG_VAR_LEN, MAX_LONG_VAR_LEN)?,
+            },
+            "DATE" => {
+                at_most(0)?;
+                Type::Date
+            }
+            "TIME" | "TIME WITHOUT TIME ZONE" => {
+                at_most(1)?;
+                Type::Time
+            }
+            "TIMETZ" | "TIME WITH TIME ZONE" => {
+                at_most(1)?;
+                Type::TimeTz
+            }
+            "TIMESTAMP" | "DATETIME" | "SMALLDATETIME" | "TIMESTAMP WITHOUT TIME ZONE" => {
+                at_most(1)?;
+                Type::Timestamp
+            }
+            "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => {
+                at_most(1)?;
+                Type::TimestampTz
+            }
+            "NUMERIC" | "DECIMAL" | "NUMBER" | "MONEY" => {
+                let (precision, scale) = match (name.as_str(), args.as_slice()) {
+                    ("MONEY", []) => (18, 4),
+                    ("NUMBER", []) => (38, 0),
+                    (_, []) => (37, 15),
+                    (_, [precision]) => (*precision, 0),
+                    (_, [precision, scale]) => (*precision, *scale),
+                    _ => return Err(invalid_arguments()),
+                };
+                if precision == 0 || scale > precision {
+                    return Err(invalid_arguments());
+                }
+                Type::Numeric { precision, scale }
+            }
+            "INTERVAL YEAR" | "INTERVAL MONTH" | "INTERVAL YEAR TO MONTH" => {
+                at_most(1)?;
+                Type::IntervalYearToMonth
+            }
+            "INTERVAL"
+            | "INTERVAL DAY"
+            | "INTERVAL HOUR"
+            | "INTERVAL MINUTE"
+            | "INTERVAL SECOND"
+            | "INTERVAL DAY TO HOUR"
+            | "INTERVAL DAY TO MINUTE"
+            | "INTERVAL DAY TO SECOND"
+            | "INTERVAL HOUR TO MINUTE"
+            | "INTERVAL HOUR TO SECOND"
+            | "INTERVAL MINUTE TO SECOND" => {
+                at_most(1)?;
+                Type::Interval
+            }
+            _ => return Err(ParseTypeError::Unknown(s.to_string())),
+        };
+        Ok(column_type)
     }
 }
 
@@ -30,6 +212,12 @@ impl fmt::Display for Type {
 pub enum ConversionError {
     #[error("unable to convert {column_type:?} to {value:?}")]
     InvalidConversion { column_type: String, value: String },
+    #[error("{value:?} overflows {column_type:?}")]
+    Overflow { column_type: String, value: String },
+    #[error("unable to parse {text:?} as {column_type}")]
+    InvalidText { column_type: String, text: String },
+    #[error("invalid {column_type:?} data {data:02x?}")]
+    InvalidData { column_type: String, data: Vec<u8> },
 }
 
 impl From<&Type> for u32 {
@@ -49,23 +237,177 @@ fn type_to_length(column: &Type) -> u32 {
         | Type::TimestampTz
         | Type::Time
         | Type::TimeTz
-        | Type::Interval => 8,
+        | Type::Interval
+        | Type::IntervalYearToMonth => 8,
 
         Type::Char { len } | Type::Binary { len } => len as u32,
 
-        Type::VarChar | Type::VarBinary => u32::MAX,
+        Type::VarChar { .. } | Type::VarBinary { .. } => u32::MAX,
 
-        Type::Numeric { precision, _scale } => numeric_width(precision),
+        Type::Numeric { precision, .. } => numeric_width(precision),
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Value<'a> {
     Null,
     Boolean(bool),
     Integer(i64),
     Float(f64),
     Char(&'a str),
+    VarChar(&'a str),
+    Binary(&'a [u8]),
+    Date(NaiveDate),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<FixedOffset>),
+    Time(NaiveTime),
+    TimeTz(NaiveTime, FixedOffset),
+    Numeric(Decimal),
+    Interval(Duration),
+    /// An INTERVAL YEAR TO MONTH, as a number of months.
+    IntervalYearToMonth(i64),
+}
+
+impl Value<'_> {
+    /// Create a TIMESTAMPTZ value from a date and time in any time zone.
+    pub fn timestamp_tz<Tz: TimeZone>(timestamp: &DateTime<Tz>) -> Value<'static> {
+        Value::TimestampTz(timestamp.with_timezone(&timestamp.offset().fix()))
+    }
+}
+
+macro_rules! from_integer {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Value<'_> {
+                fn from(i: $int) -> Self {
+                    Value::Integer(i.into())
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+/// Integers wider than INTEGER's 64 bits, which fail rather than wrap if the
+/// value does not fit.
+macro_rules! try_from_integer {
+    ($($int:ty),*) => {
+        $(
+            impl TryFrom<$int> for Value<'_> {
+                type Error = ConversionError;
+
+                fn try_from(i: $int) -> Result<Self, Self::Error> {
+                    i64::try_from(i)
+                        .map(Value::Integer)
+                        .map_err(|_| ConversionError::Overflow {
+                            column_type: Type::Integer.to_string(),
+                            value: i.to_string(),
+                        })
+                }
+            }
+        )*
+    };
+}
+
+try_from_integer!(u64, usize, isize, i128, u128);
+
+impl From<bool> for Value<'_> {
+    fn from(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+}
+
+impl From<f64> for Value<'_> {
+    fn from(f: f64) -> Self {
+        Value::Float(f)
+    }
+}
+
+impl From<f32> for Value<'_> {
+    fn from(f: f32) -> Self {
+        Value::Float(f.into())
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(s: &'a str) -> Self {
+        Value::VarChar(s)
+    }
+}
+
+impl<'a> From<&'a String> for Value<'a> {
+    fn from(s: &'a String) -> Self {
+        Value::VarChar(s)
+    }
+}
+
+impl<'a> From<&'a [u8]> for Value<'a> {
+    fn from(b: &'a [u8]) -> Self {
+        Value::Binary(b)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for Value<'a> {
+    fn from(b: &'a [u8; N]) -> Self {
+        Value::Binary(b)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for Value<'a> {
+    fn from(b: &'a Vec<u8>) -> Self {
+        Value::Binary(b)
+    }
+}
+
+impl From<NaiveDate> for Value<'_> {
+    fn from(d: NaiveDate) -> Self {
+        Value::Date(d)
+    }
+}
+
+impl From<NaiveDateTime> for Value<'_> {
+    fn from(ts: NaiveDateTime) -> Self {
+        Value::Timestamp(ts)
+    }
+}
+
+/// A TIMESTAMPTZ, keeping the time zone's offset at that instant.
+impl<Tz: TimeZone> From<DateTime<Tz>> for Value<'_> {
+    fn from(ts: DateTime<Tz>) -> Self {
+        Value::timestamp_tz(&ts)
+    }
+}
+
+impl From<NaiveTime> for Value<'_> {
+    fn from(t: NaiveTime) -> Self {
+        Value::Time(t)
+    }
+}
+
+impl From<(NaiveTime, FixedOffset)> for Value<'_> {
+    fn from((t, offset): (NaiveTime, FixedOffset)) -> Self {
+        Value::TimeTz(t, offset)
+    }
+}
+
+impl From<Decimal> for Value<'_> {
+    fn from(d: Decimal) -> Self {
+        Value::Numeric(d)
+    }
+}
+
+impl From<Duration> for Value<'_> {
+    fn from(d: Duration) -> Self {
+        Value::Interval(d)
+    }
+}
+
+/// `None` is NULL.
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
 }
 
 impl fmt::Display for Value<'_> {
@@ -81,14 +423,84 @@ impl Type {
             (Type::Boolean, Value::Boolean(b)) => buffer.push(if *b { 1u8 } else { 0u8 }),
             (Type::Integer, Value::Integer(i)) => buffer.extend_from_slice(&i.to_le_bytes()),
             (Type::Float, Value::Float(f)) => buffer.extend_from_slice(&f.to_bits().to_le_bytes()),
-            (Type::Char { len }, Value::Char(s)) => {
-                let char_len = std::cmp::min(*len, s.len());
-                let pad_len = if *len > s.len() { *len - s.len() } else { 0 };
+            (Type::Char { len }, Value::Char(s)) | (Type::Char { len }, Value::VarChar(s)) => {
+                // Truncate at a character boundary so the value stays UTF-8
+                let mut char_len = std::cmp::min(*len, s.len());
+                while !s.is_char_boundary(char_len) {
+                    char_len -= 1;
+                }
+                let pad_len = *len - char_len;
                 buffer.extend_from_slice(&s.as_bytes()[0..char_len]);
                 for _ in 0..pad_len {
                     buffer.push(0x20);
                 }
             }
+            (Type::VarChar { len }, Value::Char(s))
+            | (Type::VarChar { len }, Value::VarChar(s)) => {
+                if s.len() > *len {
+                    return Err(ConversionError::Overflow {
+                        column_type: self.to_string(),
+                        value: value.to_string(),
+                    });
+                }
+                buffer.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                buffer.extend_from_slice(s.as_bytes());
+            }
+            (Type::Binary { len }, Value::Binary(b)) => {
+                let binary_len = std::cmp::min(*len, b.len());
+                let pad_len = if *len > b.len() { *len - b.len() } else { 0 };
+                buffer.extend_from_slice(&b[0..binary_len]);
+                for _ in 0..pad_len {
+                    buffer.push(0x00);
+                }
+            }
+            (Type::VarBinary { len }, Value::Binary(b)) => {
+                if b.len() > *len {
+                    return Err(ConversionError::Overflow {
+                        column_type: self.to_string(),
+                        value: value.to_string(),
+                    });
+                }
+                buffer.extend_from_slice(&(b.len() as u32).to_le_bytes());
+                buffer.extend_from_slice(b);
+            }
+            (Type::Date, Value::Date(d)) => {
+                buffer.extend_from_slice(&d.to_y2k_epoch_duration().num_days().to_le_bytes())
+            }
+            (Type::Timestamp, Value::Timestamp(ts)) => {
+                buffer.extend_from_slice(&self.microseconds(value, ts)?.to_le_bytes())
+            }
+            (Type::TimestampTz, Value::TimestampTz(ts)) => {
+                buffer.extend_from_slice(&self.microseconds(value, &ts.naive_utc())?.to_le_bytes())
+            }
+            (Type::Time, Value::Time(t)) => {
+                buffer.extend_from_slice(&t.to_microseconds_since_midnight().to_le_bytes())
+            }
+            (Type::TimeTz, Value::TimeTz(t, offset)) => {
+                buffer.extend_from_slice(&date::timetz(t, offset).to_le_bytes())
+            }
+            (Type::Numeric { precision, scale }, Value::Numeric(d)) => {
+                let mantissa = d
+                    .rescale(*scale)
+                    .filter(|m| fits_precision(*m, *precision))
+                    .ok_or_else(|| ConversionError::Overflow {
+                        column_type: self.to_string(),
+                        value: value.to_string(),
+                    })?;
+                numeric::append_words(buffer, mantissa, numeric_width(*precision))
+            }
+            (Type::Interval, Value::Interval(d)) => {
+                let microseconds =
+                    d.num_microseconds()
+                        .ok_or_else(|| ConversionError::Overflow {
+                            column_type: self.to_string(),
+                            value: value.to_string(),
+                        })?;
+                buffer.extend_from_slice(&microseconds.to_le_bytes())
+            }
+            (Type::IntervalYearToMonth, Value::IntervalYearToMonth(months)) => {
+                buffer.extend_from_slice(&months.to_le_bytes())
+            }
             (_, value) => {
                 return Err(ConversionError::InvalidConversion {
                     column_type: self.to_string(),
@@ -98,12 +510,99 @@ impl Type {
         }
         Ok(())
     }
+
+    /// Read a non-null value of this type from its encoded bytes, excluding
+    /// the length prefix of variable width types.
+    pub fn decode<'a>(&self, data: &'a [u8]) -> Result<Value<'a>, ConversionError> {
+        let invalid = || ConversionError::InvalidData {
+            column_type: self.to_string(),
+            data: data.to_vec(),
+        };
+        let int64 = || -> Result<i64, ConversionError> {
+            let mut bytes = [0u8; 8];
+            if data.len() != bytes.len() {
+                return Err(invalid());
+            }
+            bytes.copy_from_slice(data);
+            Ok(i64::from_le_bytes(bytes))
+        };
+        let fixed = |len: usize| {
+            if data.len() == len {
+                Ok(data)
+            } else {
+                Err(invalid())
+            }
+        };
+
+        Ok(match self {
+            Type::Boolean => match data {
+                [0] => Value::Boolean(false),
+                [1] => Value::Boolean(true),
+                _ => return Err(invalid()),
+            },
+            Type::Integer => Value::Integer(int64()?),
+            Type::Float => Value::Float(f64::from_bits(int64()? as u64)),
+            Type::Char { len } => {
+                Value::Char(std::str::from_utf8(fixed(*len)?).map_err(|_| invalid())?)
+            }
+            Type::VarChar { .. } => {
+                Value::VarChar(std::str::from_utf8(data).map_err(|_| invalid())?)
+            }
+            Type::Binary { len } => Value::Binary(fixed(*len)?),
+            Type::VarBinary { .. } => Value::Binary(data),
+            Type::Date => Value::Date(date::from_y2k_epoch_days(int64()?).ok_or_else(invalid)?),
+            Type::Timestamp => {
+                Value::Timestamp(date::from_y2k_epoch_microseconds(int64()?).ok_or_else(invalid)?)
+            }
+            Type::TimestampTz => {
+                Value::TimestampTz(FixedOffset::east_opt(0).unwrap().from_utc_datetime(
+                    &date::from_y2k_epoch_microseconds(int64()?).ok_or_else(invalid)?,
+                ))
+            }
+            Type::Time => {
+                Value::Time(date::from_microseconds_since_midnight(int64()?).ok_or_else(invalid)?)
+            }
+            Type::TimeTz => {
+                let (time, offset) = date::from_timetz(int64()? as u64).ok_or_else(invalid)?;
+                Value::TimeTz(time, offset)
+            }
+            Type::Numeric { precision, scale } => {
+                let width = numeric_width(*precision) as usize;
+                let mantissa = numeric::from_words(fixed(width)?).ok_or_else(invalid)?;
+                Value::Numeric(Decimal::new(mantissa, *scale))
+            }
+            Type::Interval => Value::Interval(Duration::microseconds(int64()?)),
+            Type::IntervalYearToMonth => Value::IntervalYearToMonth(int64()?),
+        })
+    }
+
+    /// Microseconds since the Vertica epoch, 2000-01-01 00:00:00.
+    fn microseconds(
+        &self,
+        value: &Value,
+        timestamp: &NaiveDateTime,
+    ) -> Result<i64, ConversionError> {
+        timestamp
+            .to_y2k_epoch_duration()
+            .num_microseconds()
+            .ok_or_else(|| ConversionError::Overflow {
+                column_type: self.to_string(),
+                value: value.to_string(),
+            })
+    }
 }
 
 pub fn numeric_width(precision: u32) -> u32 {
     ((precision / 19) + 1) * 8
 }
 
+fn fits_precision(mantissa: i128, precision: u32) -> bool {
+    match 10u128.checked_pow(precision) {
+        Some(limit) => mantissa.unsigned_abs() < limit,
+        None => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,10 +619,11 @@ mod tests {
         assert_eq!(3, u32::from(&Type::Binary { len: 3 }));
         assert_eq!(8, u32::from(&Type::Integer));
         assert_eq!(8, u32::from(&Type::Interval));
+        assert_eq!(8, u32::from(&Type::IntervalYearToMonth));
         assert_eq!(8, u32::from(&Type::Time));
         assert_eq!(14, u32::from(&Type::Char { len: 14 }));
-        assert_eq!(u32::MAX, u32::from(&Type::VarBinary));
-        assert_eq!(u32::MAX, u32::from(&Type::VarChar));
+        assert_eq!(u32::MAX, u32::from(&Type::VarBinary { len: 10 }));
+        assert_eq!(u32::MAX, u32::from(&Type::VarChar { len: 10 }));
     }
 
     #[test]
@@ -143,7 +643,7 @@ mod tests {
         let mut out: Vec<u8> = vec![];
         assert_eq!(
             Err(InvalidConversion {
-                column_type: String::from("Boolean"),
+                column_type: String::from("BOOLEAN"),
                 value: String::from("Integer(18)"),
             }),
             Type::Boolean.append(&mut out, &Value::Integer(18))
@@ -190,4 +690,507 @@ mod tests {
             .unwrap();
         assert_eq!(vec![0x41, 0x42, 0x43], out);
     }
+
+    #[test]
+    fn char_overflow_multibyte() {
+        let mut out: Vec<u8> = vec![];
+        Type::Char { len: 1 }
+            .append(&mut out, &Value::Char("é"))
+            .unwrap();
+        assert_eq!(vec![0x20], out);
+        out.clear();
+        Type::Char { len: 4 }
+            .append(&mut out, &Value::Char("aéé"))
+            .unwrap();
+        assert_eq!(vec![0x61, 0xc3, 0xa9, 0x20], out);
+    }
+
+    #[test]
+    fn varchar() {
+        let mut out: Vec<u8> = vec![];
+        Type::VarChar { len: 3 }
+            .append(&mut out, &Value::VarChar("ONE"))
+            .unwrap();
+        assert_eq!(vec![3u8, 0, 0, 0, 0x4f, 0x4e, 0x45], out);
+    }
+
+    #[test]
+    fn varchar_empty() {
+        let mut out: Vec<u8> = vec![];
+        Type::VarChar { len: 3 }
+            .append(&mut out, &Value::VarChar(""))
+            .unwrap();
+        assert_eq!(vec![0u8, 0, 0, 0], out);
+    }
+
+    #[test]
+    fn char_from_varchar() {
+        let mut out: Vec<u8> = vec![];
+        Type::Char { len: 4 }
+            .append(&mut out, &Value::VarChar("AB"))
+            .unwrap();
+        assert_eq!(vec![0x41, 0x42, 0x20, 0x20], out);
+    }
+
+    #[test]
+    fn varbinary() {
+        let mut out: Vec<u8> = vec![];
+        Type::VarBinary { len: 2 }
+            .append(&mut out, &Value::Binary(&[0xab, 0xcd]))
+            .unwrap();
+        assert_eq!(vec![2u8, 0, 0, 0, 0xab, 0xcd], out);
+    }
+
+    #[test]
+    fn var_overflow() {
+        let mut out: Vec<u8> = vec![];
+        assert_eq!(
+            Err(ConversionError::Overflow {
+                column_type: String::from("VARCHAR(2)"),
+                value: String::from("VarChar(\"ONE\")"),
+            }),
+            Type::VarChar { len: 2 }.append(&mut out, &Value::VarChar("ONE"))
+        );
+        assert!(matches!(
+            Type::VarBinary { len: 1 }.append(&mut out, &Value::Binary(&[0xab, 0xcd])),
+            Err(ConversionError::Overflow { .. })
+        ));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn binary_underflow() {
+        let mut out: Vec<u8> = vec![];
+        Type::Binary { len: 3 }
+            .append(&mut out, &Value::Binary(&[0xab, 0xcd]))
+            .unwrap();
+        assert_eq!(vec![0xabu8, 0xcd, 0x00], out);
+    }
+
+    #[test]
+    fn binary_overflow() {
+        let mut out: Vec<u8> = vec![];
+        Type::Binary { len: 1 }
+            .append(&mut out, &Value::Binary(&[0xab, 0xcd]))
+            .unwrap();
+        assert_eq!(vec![0xabu8], out);
+    }
+
+    #[test]
+    fn date() {
+        let mut out: Vec<u8> = vec![];
+        Type::Date
+            .append(
+                &mut out,
+                &Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            )
+            .unwrap();
+        assert_eq!(vec![0x9au8, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], out);
+    }
+
+    #[test]
+    fn timestamp() {
+        let mut out: Vec<u8> = vec![];
+        Type::Timestamp
+            .append(
+                &mut out,
+                &Value::Timestamp(
+                    NaiveDate::from_ymd_opt(1999, 2, 23)
+                        .unwrap()
+                        .and_hms_micro_opt(3, 11, 52, 350_000)
+                        .unwrap(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(vec![0x30u8, 0x85, 0xb3, 0x4f, 0x7e, 0xe7, 0xff, 0xff], out);
+    }
+
+    #[test]
+    fn timestamp_tz() {
+        let mut out: Vec<u8> = vec![];
+        let timestamp = FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(1999, 1, 8, 7, 4, 37)
+            .unwrap();
+        Type::TimestampTz
+            .append(&mut out, &Value::timestamp_tz(&timestamp))
+            .unwrap();
+        assert_eq!(vec![0x40u8, 0x1f, 0x3e, 0x64, 0xe8, 0xe3, 0xff, 0xff], out);
+    }
+
+    #[test]
+    fn timestamp_tz_from_utc() {
+        let mut out: Vec<u8> = vec![];
+        let timestamp = chrono::Utc.with_ymd_and_hms(1999, 1, 8, 12, 4, 37).unwrap();
+        Type::TimestampTz
+            .append(&mut out, &Value::timestamp_tz(&timestamp))
+            .unwrap();
+        assert_eq!(vec![0x40u8, 0x1f, 0x3e, 0x64, 0xe8, 0xe3, 0xff, 0xff], out);
+    }
+
+    #[test]
+    fn time() {
+        let mut out: Vec<u8> = vec![];
+        Type::Time
+            .append(
+                &mut out,
+                &Value::Time(NaiveTime::from_hms_micro_opt(7, 9, 23, 1).unwrap()),
+            )
+            .unwrap();
+        assert_eq!(vec![0xc1u8, 0x2e, 0x98, 0xff, 0x05, 0x00, 0x00, 0x00], out);
+    }
+
+    #[test]
+    fn time_tz() {
+        let mut out: Vec<u8> = vec![];
+        Type::TimeTz
+            .append(
+                &mut out,
+                &Value::TimeTz(
+                    NaiveTime::from_hms_opt(15, 12, 34).unwrap(),
+                    FixedOffset::west_opt(5 * 3600).unwrap(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(vec![0xd0u8, 0x97, 0x01, 0x80, 0xf0, 0x79, 0xf0, 0x10], out);
+    }
+
+    #[test]
+    fn numeric() {
+        let mut out: Vec<u8> = vec![];
+        Type::Numeric {
+            precision: 18,
+            scale: 4,
+        }
+        .append(&mut out, &Value::Numeric(Decimal::new(-125, 2)))
+        .unwrap();
+        assert_eq!((-12_500i64).to_le_bytes().to_vec(), out);
+    }
+
+    #[test]
+    fn numeric_38_10() {
+        let mut out: Vec<u8> = vec![];
+        Type::Numeric {
+            precision: 38,
+            scale: 10,
+        }
+        .append(&mut out, &Value::Numeric("1234.5".parse().unwrap()))
+        .unwrap();
+        let mut expected = vec![0u8; 16];
+        expected.extend_from_slice(&12_345_000_000_000i64.to_le_bytes());
+        assert_eq!(expected, out);
+    }
+
+    #[test]
+    fn numeric_overflow() {
+        let mut out: Vec<u8> = vec![];
+        assert_eq!(
+            Err(ConversionError::Overflow {
+                column_type: String::from("NUMERIC(4,2)"),
+                value: String::from("Numeric(Decimal { mantissa: 10000, scale: 2 })"),
+            }),
+            Type::Numeric {
+                precision: 4,
+                scale: 2,
+            }
+            .append(&mut out, &Value::Numeric(Decimal::new(10000, 2)))
+        );
+        assert!(matches!(
+            Type::Numeric {
+                precision: 38,
+                scale: 0,
+            }
+            .append(&mut out, &Value::Numeric(Decimal::new(i128::MIN, 0))),
+            Err(ConversionError::Overflow { .. })
+        ));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn interval() {
+        let mut out: Vec<u8> = vec![];
+        Type::Interval
+            .append(&mut out, &Value::Interval(Duration::seconds(10_983)))
+            .unwrap();
+        assert_eq!(vec![0xc0u8, 0x47, 0xa3, 0x8e, 0x02, 0x00, 0x00, 0x00], out);
+    }
+
+    #[test]
+    fn interval_overflow() {
+        let mut out: Vec<u8> = vec![];
+        assert!(matches!(
+            Type::Interval.append(&mut out, &Value::Interval(Duration::milliseconds(i64::MAX))),
+            Err(ConversionError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn interval_year_to_month() {
+        let mut out: Vec<u8> = vec![];
+        Type::IntervalYearToMonth
+            .append(&mut out, &Value::IntervalYearToMonth(-14))
+            .unwrap();
+        assert_eq!((-14i64).to_le_bytes().to_vec(), out);
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let cases = [
+            (Type::Boolean, Value::Boolean(true)),
+            (Type::Integer, Value::Integer(-42)),
+            (Type::Float, Value::Float(-1.11)),
+            (Type::Char { len: 4 }, Value::Char("ABCD")),
+            (Type::VarChar { len: 3 }, Value::VarChar("ONE")),
+            (Type::Binary { len: 2 }, Value::Binary(&[0xab, 0xcd])),
+            (Type::VarBinary { len: 2 }, Value::Binary(&[0xab, 0xcd])),
+            (
+                Type::Date,
+                Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            ),
+            (
+                Type::Timestamp,
+                Value::Timestamp(
+                    NaiveDate::from_ymd_opt(1999, 2, 23)
+                        .unwrap()
+                        .and_hms_micro_opt(3, 11, 52, 350_000)
+                        .unwrap(),
+                ),
+            ),
+            (
+                Type::TimestampTz,
+                Value::TimestampTz(utc.with_ymd_and_hms(1999, 1, 8, 12, 4, 37).unwrap()),
+            ),
+            (
+                Type::Time,
+                Value::Time(NaiveTime::from_hms_micro_opt(7, 9, 23, 1).unwrap()),
+            ),
+            (
+                Type::TimeTz,
+                Value::TimeTz(
+                    NaiveTime::from_hms_opt(15, 12, 34).unwrap(),
+                    FixedOffset::west_opt(5 * 3600).unwrap(),
+                ),
+            ),
+            (
+                Type::Numeric {
+                    precision: 38,
+                    scale: 10,
+                },
+                Value::Numeric(Decimal::new(-12_345_000_000_000, 10)),
+            ),
+            (Type::Interval, Value::Interval(Duration::seconds(10_983))),
+            (Type::IntervalYearToMonth, Value::IntervalYearToMonth(14)),
+        ];
+        for (column_type, value) in cases.iter() {
+            let mut out: Vec<u8> = vec![];
+            column_type.append(&mut out, value).unwrap();
+            if u32::from(column_type) == u32::MAX {
+                out.drain(0..4);
+            }
+            assert_eq!(Ok(*value), column_type.decode(&out));
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(
+            Err(ConversionError::InvalidData {
+                column_type: String::from("BOOLEAN"),
+                data: vec![2],
+            }),
+            Type::Boolean.decode(&[2])
+        );
+        assert!(Type::Integer.decode(&[1, 2, 3]).is_err());
+        assert!(Type::Char { len: 2 }.decode(&[0xff, 0xfe]).is_err());
+        assert!(Type::Time.decode(&(-1i64).to_le_bytes()).is_err());
+    }
+
+    #[test]
+    fn display_parse_round_trip() {
+        let types = [
+            Type::Boolean,
+            Type::Integer,
+            Type::Float,
+            Type::Char { len: 10 },
+            Type::VarChar { len: 20 },
+            Type::VarChar { len: 1_000_000 },
+            Type::Date,
+            Type::Timestamp,
+            Type::TimestampTz,
+            Type::Time,
+            Type::TimeTz,
+            Type::VarBinary { len: 16 },
+            Type::VarBinary {
+                len: MAX_LONG_VAR_LEN,
+            },
+            Type::Binary { len: 3 },
+            Type::Numeric {
+                precision: 38,
+                scale: 10,
+            },
+            Type::Interval,
+            Type::IntervalYearToMonth,
+        ];
+        for t in types.iter() {
+            assert_eq!(Ok(*t), t.to_string().parse());
+        }
+        assert_eq!(
+            "NUMERIC(18,4)",
+            Type::Numeric {
+                precision: 18,
+                scale: 4
+            }
+            .to_string()
+        );
+        assert_eq!("VARCHAR(20)", Type::VarChar { len: 20 }.to_string());
+        assert_eq!(
+            "LONG VARCHAR(1000000)",
+            Type::VarChar { len: 1_000_000 }.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_synonyms() {
+        let cases = [
+            ("int8", Type::Integer),
+            ("BIGINT", Type::Integer),
+            ("FLOAT8", Type::Float),
+            ("double  precision", Type::Float),
+            ("FLOAT(53)", Type::Float),
+            ("BOOL", Type::Boolean),
+            ("CHAR", Type::Char { len: 1 }),
+            ("CHARACTER(10)", Type::Char { len: 10 }),
+            ("VARCHAR", Type::VarChar { len: 65_000 }),
+            ("VARCHAR(255)", Type::VarChar { len: 255 }),
+            ("character varying(20)", Type::VarChar { len: 20 }),
+            ("LONG VARCHAR", Type::VarChar { len: 1_048_576 }),
+            ("LONG VARCHAR(1000000)", Type::VarChar { len: 1_000_000 }),
+            ("BYTEA", Type::VarBinary { len: 65_000 }),
+            ("RAW(16)", Type::VarBinary { len: 16 }),
+            ("LONG VARBINARY(100000)", Type::VarBinary { len: 100_000 }),
+            ("BINARY", Type::Binary { len: 1 }),
+            ("DATETIME", Type::Timestamp),
+            ("TIMESTAMP(3) WITHOUT TIME ZONE", Type::Timestamp),
+            ("timestamp with time zone", Type::TimestampTz),
+            ("TIME(6) WITH TIME ZONE", Type::TimeTz),
+            ("TIME", Type::Time),
+            (
+                "DECIMAL(18, 4)",
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4,
+                },
+            ),
+            (
+                "NUMERIC(10)",
+                Type::Numeric {
+                    precision: 10,
+                    scale: 0,
+                },
+            ),
+            (
+                "NUMERIC",
+                Type::Numeric {
+                    precision: 37,
+                    scale: 15,
+                },
+            ),
+            (
+                "MONEY",
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4,
+                },
+            ),
+            ("INTERVAL", Type::Interval),
+            ("INTERVAL DAY TO SECOND(3)", Type::Interval),
+            ("INTERVAL HOUR TO MINUTE", Type::Interval),
+            ("INTERVAL YEAR TO MONTH", Type::IntervalYearToMonth),
+        ];
+        for (sql, t) in cases.iter() {
+            assert_eq!(Ok(*t), sql.parse(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(ParseTypeError::Unknown(String::from("GEOMETRY"))),
+            "GEOMETRY".parse::<Type>()
+        );
+        assert_eq!(
+            Err(ParseTypeError::InvalidArguments(String::from("CHAR(x)"))),
+            "CHAR(x)".parse::<Type>()
+        );
+        assert!("NUMERIC(4,5)".parse::<Type>().is_err());
+        assert!("INTEGER(4)".parse::<Type>().is_err());
+        assert!("CHAR(0)".parse::<Type>().is_err());
+        assert!("VARCHAR(10".parse::<Type>().is_err());
+        assert!("VARCHAR(65001)".parse::<Type>().is_err());
+        assert!("CHAR(65001)".parse::<Type>().is_err());
+        assert!("LONG VARBINARY(32000001)".parse::<Type>().is_err());
+    }
+
+    #[test]
+    fn from_rust_values() {
+        assert_eq!(Value::Integer(-7), Value::from(-7i8));
+        assert_eq!(Value::Integer(65_535), Value::from(u16::MAX));
+        assert_eq!(Value::Integer(4_294_967_295), Value::from(u32::MAX));
+        assert_eq!(Value::Float(1.5), Value::from(1.5f32));
+        assert_eq!(Value::Boolean(true), Value::from(true));
+        let name = String::from("Fred");
+        assert_eq!(Value::VarChar("Fred"), Value::from(&name));
+        assert_eq!(Value::Binary(&[1, 2]), Value::from(&[1u8, 2]));
+        assert_eq!(Value::Binary(&[1, 2]), Value::from(&vec![1u8, 2]));
+        assert_eq!(
+            Value::Numeric(Decimal::new(12345, 2)),
+            Value::from(Decimal::new(12345, 2))
+        );
+        assert_eq!(
+            Value::Interval(Duration::seconds(90)),
+            Value::from(Duration::seconds(90))
+        );
+        assert_eq!(Value::Null, Value::from(None::<&str>));
+        assert_eq!(Value::Integer(3), Value::from(Some(3)));
+    }
+
+    #[test]
+    fn from_chrono_values() {
+        let date = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let timestamp = date.and_hms_opt(7, 4, 37).unwrap();
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(Value::Date(date), Value::from(date));
+        assert_eq!(Value::Timestamp(timestamp), Value::from(timestamp));
+        assert_eq!(
+            Value::TimestampTz(est.from_local_datetime(&timestamp).unwrap()),
+            Value::from(est.from_local_datetime(&timestamp).unwrap())
+        );
+        assert_eq!(
+            Value::TimestampTz(chrono::Utc.from_utc_datetime(&timestamp).fixed_offset()),
+            Value::from(chrono::Utc.from_utc_datetime(&timestamp))
+        );
+        assert_eq!(Value::Time(timestamp.time()), Value::from(timestamp.time()));
+        assert_eq!(
+            Value::TimeTz(timestamp.time(), est),
+            Value::from((timestamp.time(), est))
+        );
+    }
+
+    #[test]
+    fn try_from_wide_integers() {
+        assert_eq!(Ok(Value::Integer(42)), Value::try_from(42u64));
+        assert_eq!(
+            Ok(Value::Integer(i64::MIN)),
+            Value::try_from(i64::MIN as i128)
+        );
+        assert_eq!(
+            Err(ConversionError::Overflow {
+                column_type: String::from("INTEGER"),
+                value: String::from("18446744073709551615"),
+            }),
+            Value::try_from(u64::MAX)
+        );
+        assert!(Value::try_from(u128::MAX).is_err());
+    }
 }
@@ -0,0 +1,55 @@
+use crate::error::{Error, Result};
+use std::io::Write;
+
+/// Counts of the rows a converter loaded and rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub rows: u64,
+    pub rejected: u64,
+}
+
+/// Where converters send records that fail to load, like the `REJECTED DATA`
+/// and `EXCEPTIONS` files of Vertica's COPY.
+#[derive(Default)]
+pub(crate) struct Rejects<'a> {
+    pub rejected_data: Option<Box<dyn Write + 'a>>,
+    pub exceptions: Option<Box<dyn Write + 'a>>,
+    pub abort_on_error: bool,
+}
+
+impl Rejects<'_> {
+    /// Record a rejected input record, or fail if aborting on errors. I/O
+    /// errors are never rejections, as they would affect every later row.
+    pub fn reject(
+        &mut self,
+        summary: &mut Summary,
+        line: u64,
+        data: Option<&[u8]>,
+        error: Error,
+    ) -> Result<()> {
+        if self.abort_on_error || matches!(error, Error::Io(_)) {
+            return Err(Error::Record {
+                record: line,
+                source: Box::new(error),
+            });
+        }
+        if let (Some(out), Some(data)) = (&mut self.rejected_data, data) {
+            out.write_all(data)?;
+        }
+        if let Some(out) = &mut self.exceptions {
+            writeln!(out, "line {}: {}", line, error)?;
+        }
+        summary.rejected += 1;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(out) = &mut self.rejected_data {
+            out.flush()?;
+        }
+        if let Some(out) = &mut self.exceptions {
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
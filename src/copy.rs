@@ -0,0 +1,202 @@
+use crate::ddl::quote_identifier;
+use crate::error::{Error, Result};
+use crate::schema::Schema;
+use std::fmt;
+
+/// Renders the Vertica `COPY ... NATIVE` statement that loads a VNF file
+/// written with a schema.
+///
+/// Every schema column is listed so file and table columns are matched by
+/// name. Columns marked as fillers are read from the file but not loaded.
+pub struct CopyStatement<'a> {
+    table: &'a str,
+    schema: &'a Schema,
+    source: Source<'a>,
+    local: bool,
+    gzip: bool,
+    fillers: Vec<String>,
+    abort_on_error: bool,
+    direct: bool,
+    exceptions: Option<&'a str>,
+    rejected_data: Option<&'a str>,
+}
+
+enum Source<'a> {
+    File(&'a str),
+    Stdin,
+}
+
+impl<'a> CopyStatement<'a> {
+    /// Load a file on the database node, or on the client with `local`.
+    pub fn from_file(table: &'a str, path: &'a str, schema: &'a Schema) -> CopyStatement<'a> {
+        CopyStatement::new(table, Source::File(path), schema)
+    }
+
+    /// Load from standard input, such as a VNF piped into `vsql`.
+    pub fn from_stdin(table: &'a str, schema: &'a Schema) -> CopyStatement<'a> {
+        CopyStatement::new(table, Source::Stdin, schema)
+    }
+
+    fn new(table: &'a str, source: Source<'a>, schema: &'a Schema) -> CopyStatement<'a> {
+        CopyStatement {
+            table,
+            schema,
+            source,
+            local: false,
+            gzip: false,
+            fillers: vec![],
+            abort_on_error: false,
+            direct: false,
+            exceptions: None,
+            rejected_data: None,
+        }
+    }
+
+    /// Read the file or standard input from the client rather than a node.
+    pub fn local(mut self) -> Self {
+        self.local = true;
+        self
+    }
+
+    pub fn gzip(mut self) -> Self {
+        self.gzip = true;
+        self
+    }
+
+    /// Read a schema column from the file without loading it into the table.
+    pub fn filler(mut self, column: &str) -> Result<Self> {
+        if self.schema.index_of(column).is_none() {
+            return Err(Error::UnknownColumn(column.to_string()));
+        }
+        self.fillers.push(column.to_string());
+        Ok(self)
+    }
+
+    pub fn abort_on_error(mut self) -> Self {
+        self.abort_on_error = true;
+        self
+    }
+
+    /// Load straight into ROS containers, for large files.
+    pub fn direct(mut self) -> Self {
+        self.direct = true;
+        self
+    }
+
+    pub fn exceptions(mut self, path: &'a str) -> Self {
+        self.exceptions = Some(path);
+        self
+    }
+
+    pub fn rejected_data(mut self, path: &'a str) -> Self {
+        self.rejected_data = Some(path);
+        self
+    }
+}
+
+impl fmt::Display for CopyStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "COPY {} (", self.table)?;
+        for (i, column) in self.schema.columns().iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", quote_identifier(&column.name))?;
+            if self.fillers.contains(&column.name) {
+                write!(f, " FILLER {}", column.column_type)?;
+            }
+        }
+        write!(f, ") FROM ")?;
+        if self.local {
+            write!(f, "LOCAL ")?;
+        }
+        match self.source {
+            Source::File(path) => write!(f, "{}", quote_literal(path))?,
+            Source::Stdin => write!(f, "STDIN")?,
+        }
+        if self.gzip {
+            write!(f, " GZIP")?;
+        }
+        write!(f, " NATIVE")?;
+        if self.abort_on_error {
+            write!(f, " ABORT ON ERROR")?;
+        }
+        if self.direct {
+            write!(f, " DIRECT")?;
+        }
+        if let Some(path) = self.exceptions {
+            write!(f, " EXCEPTIONS {}", quote_literal(path))?;
+        }
+        if let Some(path) = self.rejected_data {
+            write!(f, " REJECTED DATA {}", quote_literal(path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote a string literal, doubling any embedded single quotes.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::Type;
+
+    fn schema() -> Schema {
+        vec![
+            ("customer_id", Type::Integer, false),
+            ("name", Type::VarChar { len: 20 }, true),
+            ("raw", Type::VarBinary { len: 20 }, true),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn copy_from_file() {
+        assert_eq!(
+            "COPY customers (\"customer_id\", \"name\", \"raw\") \
+             FROM '/data/customers.vnf' NATIVE",
+            CopyStatement::from_file("customers", "/data/customers.vnf", &schema()).to_string()
+        );
+    }
+
+    #[test]
+    fn copy_with_options() {
+        assert_eq!(
+            "COPY staging.customers (\"customer_id\", \"name\", \"raw\" FILLER VARBINARY(20)) \
+             FROM LOCAL '/data/o''brien.vnf.gz' GZIP NATIVE ABORT ON ERROR DIRECT \
+             EXCEPTIONS '/tmp/customers.exceptions' REJECTED DATA '/tmp/customers.rejected'",
+            CopyStatement::from_file("staging.customers", "/data/o'brien.vnf.gz", &schema())
+                .local()
+                .gzip()
+                .filler("raw")
+                .unwrap()
+                .abort_on_error()
+                .direct()
+                .exceptions("/tmp/customers.exceptions")
+                .rejected_data("/tmp/customers.rejected")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn unknown_filler() {
+        assert!(matches!(
+            CopyStatement::from_stdin("customers", &schema()).filler("id"),
+            Err(Error::UnknownColumn(ref name)) if name == "id"
+        ));
+    }
+
+    #[test]
+    fn copy_from_stdin() {
+        assert_eq!(
+            "COPY customers (\"customer_id\", \"name\", \"raw\") FROM LOCAL STDIN NATIVE",
+            CopyStatement::from_stdin("customers", &schema())
+                .local()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use crate::convert::{Rejects, Summary};
+use crate::error::{Error, Result};
+use crate::text::TextFormat;
+use crate::VnfWriter;
+use std::io::{Read, Write};
+
+/// Streams CSV records into a VNF file, parsing each field as the type of
+/// the schema column at the same position.
+///
+/// Records that fail to convert are skipped and counted, and written to the
+/// rejected data and exceptions sinks if set, unless `abort_on_error` is
+/// used. Whether the first record is a header is up to the CSV reader.
+#[derive(Default)]
+pub struct CsvConverter<'a> {
+    format: TextFormat,
+    rejects: Rejects<'a>,
+}
+
+impl<'a> CsvConverter<'a> {
+    pub fn new() -> CsvConverter<'a> {
+        CsvConverter::default()
+    }
+
+    pub fn format(mut self, format: TextFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Write rejected records here, as CSV.
+    pub fn rejected_data<W: Write + 'a>(mut self, out: W) -> Self {
+        self.rejects.rejected_data = Some(Box::new(out));
+        self
+    }
+
+    /// Write the line number and reason for each rejected record here.
+    pub fn exceptions<W: Write + 'a>(mut self, out: W) -> Self {
+        self.rejects.exceptions = Some(Box::new(out));
+        self
+    }
+
+    /// Fail on the first record that does not convert.
+    pub fn abort_on_error(mut self) -> Self {
+        self.rejects.abort_on_error = true;
+        self
+    }
+
+    pub fn convert<R: Read, W: Write>(
+        &mut self,
+        reader: &mut ::csv::Reader<R>,
+        writer: &mut VnfWriter<W>,
+    ) -> Result<Summary> {
+        let mut summary = Summary::default();
+        let mut record = ::csv::StringRecord::new();
+        let mut scratch = vec![];
+        loop {
+            let line = reader.position().line();
+            match reader.read_record(&mut record) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) if e.is_io_error() => return Err(e.into()),
+                Err(e) => {
+                    let line = e.position().map_or(line, |p| p.line());
+                    self.rejects.reject(&mut summary, line, None, e.into())?;
+                    continue;
+                }
+            }
+            let line = record.position().map_or(line, |p| p.line());
+
+            match write_record(&self.format, &record, &mut scratch, writer) {
+                Ok(_) => summary.rows += 1,
+                Err(e) => {
+                    let data = if self.rejects.rejected_data.is_some() {
+                        Some(to_csv(&record)?)
+                    } else {
+                        None
+                    };
+                    self.rejects
+                        .reject(&mut summary, line, data.as_deref(), e)?;
+                }
+            }
+        }
+        self.rejects.flush()?;
+        Ok(summary)
+    }
+}
+
+fn write_record<W: Write>(
+    format: &TextFormat,
+    record: &::csv::StringRecord,
+    scratch: &mut Vec<u8>,
+    writer: &mut VnfWriter<W>,
+) -> Result<usize> {
+    if record.len() != writer.schema().len() {
+        return Err(Error::Arity {
+            expected: writer.schema().len(),
+            actual: record.len(),
+        });
+    }
+    writer.write_with(|row| {
+        for field in record.iter() {
+            let index = row.index();
+            let column = match row.column() {
+                Some(column) => column,
+                None => break,
+            };
+            let value = format
+                .parse(&column.column_type, field, scratch)
+                .map_err(|source| Error::Conversion {
+                    index,
+                    name: column.name.clone(),
+                    source,
+                })?;
+            row.push(&value)?;
+        }
+        Ok(())
+    })
+}
+
+/// Re-encode a record as a line of CSV.
+fn to_csv(record: &::csv::StringRecord) -> Result<Vec<u8>> {
+    let mut out = ::csv::Writer::from_writer(vec![]);
+    out.write_record(record)?;
+    out.into_inner().map_err(|e| Error::Io(e.into_error()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::{Type, Value};
+    use crate::schema::Schema;
+    use crate::VnfReader;
+    use chrono::NaiveDate;
+    use std::io::Cursor;
+
+    fn schema() -> Schema {
+        vec![
+            ("id", Type::Integer, false),
+            ("active", Type::Boolean, true),
+            ("joined", Type::Date, true),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn csv(data: &str) -> ::csv::Reader<&[u8]> {
+        ::csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(data.as_bytes())
+    }
+
+    fn read_back(vnf: Vec<u8>) -> Vec<Vec<String>> {
+        VnfReader::new(Cursor::new(vnf))
+            .unwrap()
+            .map(|row| {
+                row.unwrap()
+                    .values(&schema())
+                    .unwrap()
+                    .iter()
+                    .map(|v| format!("{:?}", v))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn convert() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let summary = CsvConverter::new()
+            .convert(
+                &mut csv("id,active,joined\n1,t,1999-01-08\n2,no,\n"),
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(
+            Summary {
+                rows: 2,
+                rejected: 0
+            },
+            summary
+        );
+        let mut expected = VnfWriter::new(vec![], schema());
+        expected
+            .write_row(&[
+                Value::Integer(1),
+                Value::Boolean(true),
+                Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            ])
+            .unwrap();
+        expected
+            .write_row(&[Value::Integer(2), Value::Boolean(false), Value::Null])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn rejects() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let mut rejected = vec![];
+        let mut exceptions = vec![];
+        let summary = CsvConverter::new()
+            .format(TextFormat::default().date_format("%d/%m/%Y"))
+            .rejected_data(&mut rejected)
+            .exceptions(&mut exceptions)
+            .convert(
+                &mut csv("id,active,joined\n\
+                          1,maybe,\n\
+                          ,t,\n\
+                          3,f,08/01/1999\n\
+                          4,f\n"),
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(
+            Summary {
+                rows: 1,
+                rejected: 3
+            },
+            summary
+        );
+        assert_eq!(1, read_back(writer.finish().unwrap()).len());
+        assert_eq!("1,maybe,\n,t,\n4,f\n", String::from_utf8(rejected).unwrap());
+        assert_eq!(
+            "line 2: column `active` (index 1): unable to parse \"maybe\" as BOOLEAN\n\
+             line 3: column `id` (index 0) is NOT NULL\n\
+             line 5: row has 2 values, expected 3 columns\n",
+            String::from_utf8(exceptions).unwrap()
+        );
+    }
+
+    #[test]
+    fn abort_on_error() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let error = CsvConverter::new()
+            .abort_on_error()
+            .convert(&mut csv("id,active,joined\n1,t,\nx,t,\n"), &mut writer)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Record { record: 3, ref source } if matches!(**source, Error::Conversion { index: 0, .. })
+        ));
+        assert_eq!(1, read_back(writer.finish().unwrap()).len());
+    }
+}
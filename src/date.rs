@@ -1,4 +1,4 @@
-use chrono::{Duration, NaiveDate, NaiveDateTime};
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 
 pub trait VerticaDate {
     fn to_y2k_epoch_duration(&self) -> Duration;
@@ -6,27 +6,79 @@ pub trait VerticaDate {
 
 impl VerticaDate for NaiveDate {
     fn to_y2k_epoch_duration(&self) -> Duration {
-        *self - NaiveDate::from_ymd(2000, 1, 1)
+        *self - NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
     }
 }
 
 impl VerticaDate for NaiveDateTime {
     fn to_y2k_epoch_duration(&self) -> Duration {
-        *self - NaiveDate::from_ymd(2000, 1, 1).and_hms_micro(0, 0, 0, 0)
+        *self
+            - NaiveDate::from_ymd_opt(2000, 1, 1)
+                .unwrap()
+                .and_hms_micro_opt(0, 0, 0, 0)
+                .unwrap()
     }
 }
 
-fn seconds_since_midnight(hours: i32, minutes: i32, seconds: i32) -> i32 {
-    3600 * hours + 60 * minutes + seconds
+fn y2k_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .unwrap()
+        .and_hms_micro_opt(0, 0, 0, 0)
+        .unwrap()
 }
 
-fn microseconds_since_midnight(hours: i32, minutes: i32, seconds: i32) -> u64 {
-    1_000_000u64 * seconds_since_midnight(hours, minutes, seconds) as u64
+/// The date a number of days from 2000-01-01, if it is in range.
+pub fn from_y2k_epoch_days(days: i64) -> Option<NaiveDate> {
+    Duration::try_days(days).and_then(|d| y2k_epoch().date().checked_add_signed(d))
 }
 
-pub fn _timetz(hours: i32, minutes: i32, seconds: i32, timezone: i32) -> u64 {
-    (microseconds_since_midnight(hours - timezone, minutes, seconds) << 24)
-        | seconds_since_midnight(24 - timezone, 0, 0) as u64
+/// The date and time a number of microseconds from 2000-01-01 00:00:00, if it
+/// is in range.
+pub fn from_y2k_epoch_microseconds(microseconds: i64) -> Option<NaiveDateTime> {
+    y2k_epoch().checked_add_signed(Duration::microseconds(microseconds))
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const MICROSECONDS_PER_DAY: i64 = SECONDS_PER_DAY * 1_000_000;
+
+pub trait VerticaTime {
+    fn to_microseconds_since_midnight(&self) -> i64;
+}
+
+impl VerticaTime for NaiveTime {
+    fn to_microseconds_since_midnight(&self) -> i64 {
+        1_000_000 * self.num_seconds_from_midnight() as i64 + (self.nanosecond() / 1_000) as i64
+    }
+}
+
+/// The time a number of microseconds after midnight, if less than a day.
+pub fn from_microseconds_since_midnight(microseconds: i64) -> Option<NaiveTime> {
+    if !(0..MICROSECONDS_PER_DAY).contains(&microseconds) {
+        return None;
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt(
+        (microseconds / 1_000_000) as u32,
+        (microseconds % 1_000_000) as u32 * 1_000,
+    )
+}
+
+/// Pack a TIMETZ into 64 bits. The upper 40 bits hold the microseconds since
+/// midnight in UTC, the lower 24 bits hold the time zone as seconds offset
+/// from 24:00, which keeps the stored value positive.
+pub fn timetz(time: &NaiveTime, offset: &FixedOffset) -> u64 {
+    let offset_seconds = offset.local_minus_utc() as i64;
+    let utc = (time.to_microseconds_since_midnight() - 1_000_000 * offset_seconds)
+        .rem_euclid(MICROSECONDS_PER_DAY);
+    ((utc as u64) << 24) | (SECONDS_PER_DAY - offset_seconds) as u64
+}
+
+/// Unpack a TIMETZ into the local time and its offset from UTC.
+pub fn from_timetz(packed: u64) -> Option<(NaiveTime, FixedOffset)> {
+    let offset_seconds = SECONDS_PER_DAY - (packed & 0xff_ffff) as i64;
+    let offset = FixedOffset::east_opt(offset_seconds as i32)?;
+    let utc = (packed >> 24) as i64;
+    let local = (utc + 1_000_000 * offset_seconds).rem_euclid(MICROSECONDS_PER_DAY);
+    Some((from_microseconds_since_midnight(local)?, offset))
 }
 
 #[cfg(test)]
@@ -37,40 +89,47 @@ mod tests {
     fn test_vertica_epoch_days() {
         assert_eq!(
             -358,
-            NaiveDate::from_ymd(1999, 1, 8)
+            NaiveDate::from_ymd_opt(1999, 1, 8)
+                .unwrap()
                 .to_y2k_epoch_duration()
                 .num_days()
         );
         assert_eq!(
             0,
-            NaiveDate::from_ymd(2000, 1, 1)
+            NaiveDate::from_ymd_opt(2000, 1, 1)
+                .unwrap()
                 .to_y2k_epoch_duration()
                 .num_days()
         );
         assert_eq!(
             366,
-            NaiveDate::from_ymd(2001, 1, 1)
+            NaiveDate::from_ymd_opt(2001, 1, 1)
+                .unwrap()
                 .to_y2k_epoch_duration()
                 .num_days()
         );
     }
 
-    #[test]
-    fn test_seconds_since_midnight() {
-        assert_eq!(
-            [0xd0u8, 0x97, 0x1, 0x0],
-            seconds_since_midnight(29, 0, 0).to_le_bytes()
-        )
+    fn time(hour: u32, min: u32, sec: u32, micro: u32) -> NaiveTime {
+        NaiveTime::from_hms_micro_opt(hour, min, sec, micro).unwrap()
     }
 
     #[test]
     fn test_microseconds_since_midnight() {
-        assert_eq!(1_000_000u64, microseconds_since_midnight(0, 0, 1));
-        assert_eq!(61_000_000u64, microseconds_since_midnight(0, 1, 1));
-        assert_eq!(3661_000_000u64, microseconds_since_midnight(1, 1, 1));
+        assert_eq!(1_000_000, time(0, 0, 1, 0).to_microseconds_since_midnight());
+        assert_eq!(
+            61_000_000,
+            time(0, 1, 1, 0).to_microseconds_since_midnight()
+        );
+        assert_eq!(
+            3_661_000_001,
+            time(1, 1, 1, 1).to_microseconds_since_midnight()
+        );
         assert_eq!(
             [0x80u8, 0xf0, 0x79, 0xf0, 0x10, 0, 0, 0],
-            microseconds_since_midnight(20, 12, 34).to_le_bytes()
+            time(20, 12, 34, 0)
+                .to_microseconds_since_midnight()
+                .to_le_bytes()
         )
     }
 
@@ -79,7 +138,66 @@ mod tests {
         // TIMETZ - 15:12:34-05
         assert_eq!(
             [0xd0u8, 0x97, 0x01, 0x80, 0xf0, 0x79, 0xf0, 0x10],
-            _timetz(15, 12, 34, -5).to_le_bytes()
+            timetz(
+                &time(15, 12, 34, 0),
+                &FixedOffset::west_opt(5 * 3600).unwrap()
+            )
+            .to_le_bytes()
         )
     }
+
+    #[test]
+    fn test_timetz_half_hour_offset() {
+        // TIMETZ - 03:10:00.5+05:30 is 21:40:00.5 UTC the previous day
+        let packed = timetz(
+            &time(3, 10, 0, 500_000),
+            &FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap(),
+        );
+        assert_eq!(
+            time(21, 40, 0, 500_000).to_microseconds_since_midnight() as u64,
+            packed >> 24
+        );
+        assert_eq!(86_400 - 19_800, packed & 0xff_ffff);
+    }
+
+    #[test]
+    fn test_from_y2k_epoch() {
+        assert_eq!(
+            NaiveDate::from_ymd_opt(1999, 1, 8),
+            from_y2k_epoch_days(-358)
+        );
+        assert_eq!(None, from_y2k_epoch_days(i64::MAX));
+        assert_eq!(
+            NaiveDate::from_ymd_opt(1999, 2, 23)
+                .unwrap()
+                .and_hms_micro_opt(3, 11, 52, 350_000),
+            from_y2k_epoch_microseconds(-26_945_287_650_000)
+        );
+    }
+
+    #[test]
+    fn test_from_microseconds_since_midnight() {
+        assert_eq!(
+            Some(time(1, 1, 1, 1)),
+            from_microseconds_since_midnight(3_661_000_001)
+        );
+        assert_eq!(None, from_microseconds_since_midnight(-1));
+        assert_eq!(None, from_microseconds_since_midnight(MICROSECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn test_from_timetz() {
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let local = time(3, 10, 0, 500_000);
+        assert_eq!(Some((local, offset)), from_timetz(timetz(&local, &offset)));
+        assert_eq!(
+            Some((
+                time(15, 12, 34, 0),
+                FixedOffset::west_opt(5 * 3600).unwrap()
+            )),
+            from_timetz(u64::from_le_bytes([
+                0xd0, 0x97, 0x01, 0x80, 0xf0, 0x79, 0xf0, 0x10
+            ]))
+        );
+    }
 }
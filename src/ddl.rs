@@ -0,0 +1,192 @@
+use crate::schema::Schema;
+use std::fmt;
+
+/// Renders a Vertica `CREATE TABLE` statement matching a schema, so a
+/// staging table always has the same columns as the files loaded into it.
+///
+/// The table name is written as given, so it may be qualified with a schema
+/// name. Column names are always quoted.
+pub struct CreateTable<'a> {
+    table: &'a str,
+    schema: &'a Schema,
+    if_not_exists: bool,
+    order_by: Vec<String>,
+    segmentation: Option<Segmentation>,
+}
+
+enum Segmentation {
+    Hash(Vec<String>),
+    Unsegmented,
+}
+
+impl<'a> CreateTable<'a> {
+    pub fn new(table: &'a str, schema: &'a Schema) -> CreateTable<'a> {
+        CreateTable {
+            table,
+            schema,
+            if_not_exists: false,
+            order_by: vec![],
+            segmentation: None,
+        }
+    }
+
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// Sort order of the table's super projection.
+    pub fn order_by(mut self, columns: &[&str]) -> Self {
+        self.order_by = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Segment the super projection across all nodes by a hash of columns.
+    pub fn segmented_by_hash(mut self, columns: &[&str]) -> Self {
+        self.segmentation = Some(Segmentation::Hash(
+            columns.iter().map(|c| c.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Replicate the super projection on all nodes, for small tables.
+    pub fn unsegmented(mut self) -> Self {
+        self.segmentation = Some(Segmentation::Unsegmented);
+        self
+    }
+}
+
+impl fmt::Display for CreateTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CREATE TABLE ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        writeln!(f, "{} (", self.table)?;
+        for (i, column) in self.schema.columns().iter().enumerate() {
+            write!(
+                f,
+                "    {} {}",
+                quote_identifier(&column.name),
+                column.column_type
+            )?;
+            if !column.nullable {
+                write!(f, " NOT NULL")?;
+            }
+            writeln!(f, "{}", if i + 1 < self.schema.len() { "," } else { "" })?;
+        }
+        write!(f, ")")?;
+        if !self.order_by.is_empty() {
+            write!(f, "\nORDER BY {}", identifier_list(&self.order_by))?;
+        }
+        match &self.segmentation {
+            Some(Segmentation::Hash(columns)) => write!(
+                f,
+                "\nSEGMENTED BY HASH({}) ALL NODES",
+                identifier_list(columns)
+            ),
+            Some(Segmentation::Unsegmented) => write!(f, "\nUNSEGMENTED ALL NODES"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Quote an identifier, doubling any embedded double quotes.
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+pub(crate) fn identifier_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| quote_identifier(n))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::Type;
+
+    fn schema() -> Schema {
+        vec![
+            ("customer_id", Type::Integer, false),
+            ("name", Type::VarChar { len: 20 }, true),
+            (
+                "balance",
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4,
+                },
+                true,
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn create_table() {
+        assert_eq!(
+            "CREATE TABLE staging.customers (\n\
+             \x20   \"customer_id\" INTEGER NOT NULL,\n\
+             \x20   \"name\" VARCHAR(20),\n\
+             \x20   \"balance\" NUMERIC(18,4)\n\
+             )",
+            CreateTable::new("staging.customers", &schema()).to_string()
+        );
+    }
+
+    #[test]
+    fn create_table_segmented() {
+        assert_eq!(
+            "CREATE TABLE IF NOT EXISTS customers (\n\
+             \x20   \"customer_id\" INTEGER NOT NULL,\n\
+             \x20   \"name\" VARCHAR(20),\n\
+             \x20   \"balance\" NUMERIC(18,4)\n\
+             )\n\
+             ORDER BY \"customer_id\", \"name\"\n\
+             SEGMENTED BY HASH(\"customer_id\") ALL NODES",
+            CreateTable::new("customers", &schema())
+                .if_not_exists()
+                .order_by(&["customer_id", "name"])
+                .segmented_by_hash(&["customer_id"])
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn create_table_unsegmented() {
+        assert!(CreateTable::new("customers", &schema())
+            .unsegmented()
+            .to_string()
+            .ends_with(")\nUNSEGMENTED ALL NODES"));
+    }
+
+    #[test]
+    fn declared_lengths_round_trip() {
+        let schema: Schema = vec![
+            ("name", "VARCHAR(20)".parse().unwrap(), true),
+            ("logo", "LONG VARBINARY(100000)".parse().unwrap(), false),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            "CREATE TABLE logos (\n\
+             \x20   \"name\" VARCHAR(20),\n\
+             \x20   \"logo\" LONG VARBINARY(100000) NOT NULL\n\
+             )",
+            CreateTable::new("logos", &schema).to_string()
+        );
+        assert_eq!(
+            vec![Type::VarChar { len: 20 }, Type::VarBinary { len: 100_000 }],
+            schema.column_types()
+        );
+    }
+
+    #[test]
+    fn quoted_identifier() {
+        assert_eq!("\"say \"\"hi\"\"\"", quote_identifier("say \"hi\""));
+    }
+}
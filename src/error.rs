@@ -1,11 +1,60 @@
-#[macro_use]
-extern crate error_chain;
-
-mod errors {
-    error_chain!{
-        foreign_links {
-            Io(::std::io::Error);
-        }
-    }
+use crate::column::ConversionError;
+use std::io;
+use thiserror::Error;
 
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("row has {actual} values, expected {expected} columns")]
+    Arity { expected: usize, actual: usize },
+    #[error("invalid file header: {0}")]
+    InvalidHeader(String),
+    #[error("column index {index} is {actual} bytes wide, expected {expected}")]
+    ColumnWidth {
+        index: usize,
+        expected: u32,
+        actual: u32,
+    },
+    #[error("invalid row: {0}")]
+    InvalidRow(String),
+    #[error("column `{name}` (index {index}): {source}")]
+    Conversion {
+        index: usize,
+        name: String,
+        source: ConversionError,
+    },
+    #[error("column `{name}` (index {index}) is NOT NULL")]
+    NotNull { index: usize, name: String },
+    #[cfg(feature = "csv")]
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// An error in a record of the input, numbered from 1. Text inputs
+    /// number records by the line they start on.
+    #[error("record {record}: {source}")]
+    Record { record: u64, source: Box<Error> },
+    #[error("{0}")]
+    Serialize(String),
+    #[error("field `{name}`: {source}")]
+    Field { name: String, source: Box<Error> },
+    #[error("record schema does not match the writer's schema")]
+    SchemaMismatch,
+    #[error("no column named `{0}`")]
+    UnknownColumn(String),
+    #[error("unsupported column type: {0}")]
+    UnsupportedType(String),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "json")]
+    #[error("JSON pointer `{0}` must be empty or start with `/`")]
+    InvalidPointer(String),
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
 }
+
+pub type Result<T> = std::result::Result<T, Error>;
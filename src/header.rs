@@ -1,5 +1,4 @@
 use crate::column::Type;
-use std::u8;
 
 pub const SIGNATURE: [u8; 11] = [78, 65, 84, 73, 86, 69, 10, 255, 13, 10, 0];
 pub const VERSION: [u8; 2] = [1, 0];
@@ -61,7 +60,7 @@ mod tests {
                 1, 0, // number_of_columns
                 255, 255, 255, 255, // column_widths
             ],
-            to_header(&[Type::VarChar])
+            to_header(&[Type::VarChar { len: 20 }])
         );
     }
 
@@ -77,7 +76,7 @@ mod tests {
                 255, 255, 255, 255, // column_widths
                 4, 0, 0, 0, // column_widths
             ],
-            to_header(&[Type::VarChar, Type::Char { len: 4 }])
+            to_header(&[Type::VarChar { len: 20 }, Type::Char { len: 4 }])
         );
     }
 
@@ -93,7 +92,7 @@ mod tests {
         expected.extend(&FILLER);
         expected.extend(&number_of_columns);
         expected.extend(column_widths);
-        assert_eq!(expected, to_header(&[Type::VarBinary; 255]));
+        assert_eq!(expected, to_header(&[Type::VarBinary { len: 20 }; 255]));
     }
 
     #[test]
@@ -108,7 +107,7 @@ mod tests {
         expected.extend(&FILLER);
         expected.extend(&number_of_columns);
         expected.extend(column_widths);
-        assert_eq!(expected, to_header(&[Type::VarBinary; 256]));
+        assert_eq!(expected, to_header(&[Type::VarBinary { len: 20 }; 256]));
     }
 
     #[test]
@@ -123,6 +122,6 @@ mod tests {
         expected.extend(&FILLER);
         expected.extend(&number_of_columns);
         expected.extend(column_widths);
-        assert_eq!(expected, to_header(&[Type::VarBinary; 257]));
+        assert_eq!(expected, to_header(&[Type::VarBinary { len: 20 }; 257]));
     }
 }
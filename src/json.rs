@@ -0,0 +1,525 @@
+use crate::column::{ConversionError, Type, Value};
+use crate::convert::{Rejects, Summary};
+use crate::error::{Error, Result};
+use crate::numeric::Decimal;
+use crate::text::{decode_hex, TextFormat};
+use crate::VnfWriter;
+use base64::Engine;
+use serde_json::value::RawValue;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+
+/// How binary columns are encoded in JSON strings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum BinaryEncoding {
+    /// Standard base64 with padding.
+    #[default]
+    Base64,
+    /// Hex, optionally prefixed by `0x` or `\x`.
+    Hex,
+}
+
+/// Streams JSON Lines into a VNF file, one object per line.
+///
+/// Each column is read from the object member of the same name, or from a
+/// JSON pointer set with `path`. Missing members and `null` are NULL.
+/// Strings are parsed with the text format, so dates and timestamps may be
+/// ISO-8601, and text columns take any other JSON value as its JSON text.
+/// NUMERIC columns take numbers as written, without rounding through f64.
+///
+/// Lines that fail to convert are skipped and counted, and written to the
+/// rejected data and exceptions sinks if set, unless `abort_on_error` is
+/// used. Blank lines are ignored.
+#[derive(Default)]
+pub struct JsonConverter<'a> {
+    format: TextFormat,
+    binary_encoding: BinaryEncoding,
+    paths: HashMap<String, String>,
+    rejects: Rejects<'a>,
+}
+
+impl<'a> JsonConverter<'a> {
+    pub fn new() -> JsonConverter<'a> {
+        JsonConverter::default()
+    }
+
+    pub fn format(mut self, format: TextFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn binary_encoding(mut self, binary_encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = binary_encoding;
+        self
+    }
+
+    /// Read a column from a JSON pointer, such as `/user/id`, instead of
+    /// the member named after it. `convert` fails if the schema has no such
+    /// column or the pointer does not start with `/`.
+    pub fn path(mut self, column: &str, pointer: &str) -> Self {
+        self.paths.insert(column.to_string(), pointer.to_string());
+        self
+    }
+
+    /// Write rejected lines here, as they were read.
+    pub fn rejected_data<W: Write + 'a>(mut self, out: W) -> Self {
+        self.rejects.rejected_data = Some(Box::new(out));
+        self
+    }
+
+    /// Write the line number and reason for each rejected line here.
+    pub fn exceptions<W: Write + 'a>(mut self, out: W) -> Self {
+        self.rejects.exceptions = Some(Box::new(out));
+        self
+    }
+
+    /// Fail on the first line that does not convert.
+    pub fn abort_on_error(mut self) -> Self {
+        self.rejects.abort_on_error = true;
+        self
+    }
+
+    pub fn convert<R: BufRead, W: Write>(
+        &mut self,
+        mut reader: R,
+        writer: &mut VnfWriter<W>,
+    ) -> Result<Summary> {
+        if let Some(column) = self
+            .paths
+            .keys()
+            .find(|column| writer.schema().index_of(column).is_none())
+        {
+            return Err(Error::UnknownColumn(column.clone()));
+        }
+        if let Some(pointer) = self
+            .paths
+            .values()
+            .find(|pointer| !pointer.is_empty() && !pointer.starts_with('/'))
+        {
+            return Err(Error::InvalidPointer(pointer.clone()));
+        }
+
+        let mut summary = Summary::default();
+        let mut line = vec![];
+        let mut scratch = vec![];
+        let mut number = 0;
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            number += 1;
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let result = std::str::from_utf8(&line)
+                .map_err(|_| Error::InvalidRow(String::from("invalid UTF-8")))
+                .and_then(|text| {
+                    let json = serde_json::from_str(text)?;
+                    self.write_object(text, &json, &mut scratch, writer)
+                });
+            match result {
+                Ok(_) => summary.rows += 1,
+                Err(e) => {
+                    if !line.ends_with(b"\n") {
+                        line.push(b'\n');
+                    }
+                    self.rejects.reject(&mut summary, number, Some(&line), e)?;
+                }
+            }
+        }
+        self.rejects.flush()?;
+        Ok(summary)
+    }
+
+    fn write_object<W: Write>(
+        &self,
+        text: &str,
+        json: &Json,
+        scratch: &mut Vec<u8>,
+        writer: &mut VnfWriter<W>,
+    ) -> Result<usize> {
+        if !json.is_object() {
+            return Err(Error::InvalidRow(String::from("expected a JSON object")));
+        }
+        writer.write_with(|row| {
+            while let Some(column) = row.column() {
+                let index = row.index();
+                let pointer = self.paths.get(&column.name);
+                let field = match pointer {
+                    Some(pointer) => json.pointer(pointer),
+                    None => json.get(&column.name),
+                };
+                // serde_json rounds fractions to an f64, so read the digits
+                let number = match (&column.column_type, field) {
+                    (Type::Numeric { .. }, Some(Json::Number(_))) => {
+                        raw_field(text, &column.name, pointer.map(String::as_str))
+                    }
+                    _ => None,
+                };
+                let value = self
+                    .to_value(&column.column_type, field, number, scratch)
+                    .map_err(|source| Error::Conversion {
+                        index,
+                        name: column.name.clone(),
+                        source,
+                    })?;
+                row.push(&value)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn to_value<'v>(
+        &self,
+        column_type: &Type,
+        json: Option<&'v Json>,
+        number: Option<&str>,
+        scratch: &'v mut Vec<u8>,
+    ) -> std::result::Result<Value<'v>, ConversionError> {
+        let invalid = |json: &Json| ConversionError::InvalidConversion {
+            column_type: column_type.to_string(),
+            value: json.to_string(),
+        };
+        let json = match json {
+            None | Some(Json::Null) => return Ok(Value::Null),
+            Some(json) => json,
+        };
+        match (column_type, json) {
+            (Type::Char { .. }, Json::String(s)) => Ok(Value::Char(s)),
+            (Type::VarChar { .. }, Json::String(s)) => Ok(Value::VarChar(s)),
+            (Type::Char { .. }, json) => Ok(Value::Char(json_text(json, scratch))),
+            (Type::VarChar { .. }, json) => Ok(Value::VarChar(json_text(json, scratch))),
+            (Type::Binary { .. } | Type::VarBinary { .. }, Json::String(s)) => {
+                scratch.clear();
+                let decoded = match self.binary_encoding {
+                    BinaryEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                        .decode_vec(s, scratch)
+                        .ok(),
+                    BinaryEncoding::Hex => decode_hex(s.trim(), scratch),
+                };
+                match decoded {
+                    Some(()) => Ok(Value::Binary(scratch)),
+                    None => Err(ConversionError::InvalidText {
+                        column_type: column_type.to_string(),
+                        text: s.clone(),
+                    }),
+                }
+            }
+            (_, Json::String(s)) => self.format.parse(column_type, s, scratch),
+            (Type::Boolean, Json::Bool(b)) => Ok(Value::Boolean(*b)),
+            (Type::Integer | Type::IntervalYearToMonth, Json::Number(n)) => {
+                let i = match (n.as_i64(), n.as_u64()) {
+                    (Some(i), _) => i,
+                    (None, Some(_)) => {
+                        return Err(ConversionError::Overflow {
+                            column_type: column_type.to_string(),
+                            value: n.to_string(),
+                        })
+                    }
+                    (None, None) => return Err(invalid(json)),
+                };
+                Ok(match column_type {
+                    Type::Integer => Value::Integer(i),
+                    _ => Value::IntervalYearToMonth(i),
+                })
+            }
+            (Type::Float, Json::Number(n)) => {
+                n.as_f64().map(Value::Float).ok_or_else(|| invalid(json))
+            }
+            (Type::Numeric { .. }, Json::Number(_)) => match number.and_then(to_decimal) {
+                Some(decimal) => Ok(Value::Numeric(decimal)),
+                None => Err(ConversionError::InvalidConversion {
+                    column_type: column_type.to_string(),
+                    value: number.map_or_else(|| json.to_string(), String::from),
+                }),
+            },
+            (_, json) => Err(invalid(json)),
+        }
+    }
+}
+
+/// The text of an object member, or of a JSON pointer's target, as written.
+fn raw_field<'j>(text: &'j str, member: &str, pointer: Option<&str>) -> Option<&'j str> {
+    let tokens: Vec<String> = match pointer {
+        Some(pointer) => pointer
+            .split('/')
+            .skip(1)
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+        None => vec![member.to_string()],
+    };
+    let mut raw: &RawValue = serde_json::from_str(text).ok()?;
+    for token in tokens {
+        raw = if raw.get().starts_with('{') {
+            let object: HashMap<String, &RawValue> = serde_json::from_str(raw.get()).ok()?;
+            object.get(&token).copied()?
+        } else {
+            let array: Vec<&RawValue> = serde_json::from_str(raw.get()).ok()?;
+            array.get(token.parse::<usize>().ok()?).copied()?
+        };
+    }
+    Some(raw.get())
+}
+
+/// A decimal from a JSON number's digits and exponent.
+fn to_decimal(number: &str) -> Option<Decimal> {
+    let (digits, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], number[i + 1..].parse::<i64>().ok()?),
+        None => (number, 0),
+    };
+    let decimal: Decimal = digits.parse().ok()?;
+    let scale = i64::from(decimal.scale).checked_sub(exponent)?;
+    if scale >= 0 {
+        Some(Decimal::new(decimal.mantissa, u32::try_from(scale).ok()?))
+    } else {
+        let factor = 10i128.checked_pow(u32::try_from(-scale).ok()?)?;
+        Some(Decimal::new(decimal.mantissa.checked_mul(factor)?, 0))
+    }
+}
+
+/// The JSON text of a value, written to `scratch`.
+fn json_text<'v>(json: &Json, scratch: &'v mut Vec<u8>) -> &'v str {
+    scratch.clear();
+    serde_json::to_writer(&mut *scratch, json).expect("JSON values serialize");
+    std::str::from_utf8(scratch).expect("JSON text is UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row;
+    use crate::schema::Schema;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn schema() -> Schema {
+        vec![
+            ("id", Type::Integer, false),
+            ("name", Type::VarChar { len: 20 }, true),
+            ("joined", Type::Date, true),
+            ("seen", Type::TimestampTz, true),
+            ("token", Type::VarBinary { len: 20 }, true),
+            (
+                "balance",
+                Type::Numeric {
+                    precision: 10,
+                    scale: 2,
+                },
+                true,
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn convert() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let summary = JsonConverter::new()
+            .path("name", "/user/name")
+            .convert(
+                &br#"{"id": 1, "user": {"name": "Fred"}, "joined": "1999-01-08", "seen": "1999-01-08T11:04:37Z", "token": "q80=", "balance": 12.5}
+
+{"id": 2, "user": {"name": ["a", 1]}, "extra": true}
+"#[..],
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(
+            Summary {
+                rows: 2,
+                rejected: 0
+            },
+            summary
+        );
+
+        let joined = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let seen = Utc.from_utc_datetime(&joined.and_hms_opt(11, 4, 37).unwrap());
+        let mut expected = VnfWriter::new(vec![], schema());
+        expected
+            .write_row(row![
+                1,
+                "Fred",
+                joined,
+                seen,
+                &[0xab_u8, 0xcd],
+                Decimal::new(1250, 2)
+            ])
+            .unwrap();
+        expected
+            .write_row(row![
+                2,
+                r#"["a",1]"#,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null
+            ])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn hex_binary() {
+        let mut writer = VnfWriter::new(vec![], &[Type::Binary { len: 2 }]);
+        let summary = JsonConverter::new()
+            .binary_encoding(BinaryEncoding::Hex)
+            .path("column_0", "/raw")
+            .convert(&b"{\"raw\": \"0xabcd\"}"[..], &mut writer)
+            .unwrap();
+        assert_eq!(1, summary.rows);
+
+        let mut expected = VnfWriter::new(vec![], &[Type::Binary { len: 2 }]);
+        expected.write_row(row![&[0xab_u8, 0xcd]]).unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn numeric_digits() {
+        let column_type = Type::Numeric {
+            precision: 19,
+            scale: 2,
+        };
+        let mut writer = VnfWriter::new(vec![], &[column_type]);
+        JsonConverter::new()
+            .abort_on_error()
+            .convert(
+                &b"{\"column_0\": 12345678901234567.89}\n\
+                   {\"column_0\": -1.25e2}\n\
+                   {\"column_0\": 5E-1}\n"[..],
+                &mut writer,
+            )
+            .unwrap();
+
+        let mut expected = VnfWriter::new(vec![], &[column_type]);
+        expected
+            .write_row(row![Decimal::new(1_234_567_890_123_456_789, 2)])
+            .unwrap();
+        expected.write_row(row![Decimal::new(-125, 0)]).unwrap();
+        expected.write_row(row![Decimal::new(5, 1)]).unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+
+        let mut writer = VnfWriter::new(vec![], &[column_type]);
+        JsonConverter::new()
+            .abort_on_error()
+            .path("column_0", "/a~1b/1")
+            .convert(&b"{\"a/b\": [0, 12345678901234567.89]}\n"[..], &mut writer)
+            .unwrap();
+
+        let mut expected = VnfWriter::new(vec![], &[column_type]);
+        expected
+            .write_row(row![Decimal::new(1_234_567_890_123_456_789, 2)])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn numeric_exponent_overflow() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let mut exceptions = vec![];
+        let summary = JsonConverter::new()
+            .exceptions(&mut exceptions)
+            .convert(
+                &b"{\"id\": 1, \"balance\": 1e-9223372036854775808}\n\
+                   {\"id\": 2, \"balance\": 1e9223372036854775807}\n"[..],
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(
+            Summary {
+                rows: 0,
+                rejected: 2
+            },
+            summary
+        );
+        assert!(String::from_utf8(exceptions)
+            .unwrap()
+            .starts_with("line 1: column `balance` (index 5): unable to convert"));
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        assert!(matches!(
+            JsonConverter::new()
+                .path("nmae", "/user/name")
+                .convert(&b"{\"id\": 1}\n"[..], &mut writer),
+            Err(Error::UnknownColumn(ref name)) if name == "nmae"
+        ));
+        assert_eq!(0, writer.bytes_written());
+
+        assert!(matches!(
+            JsonConverter::new()
+                .path("name", "user/name")
+                .convert(&b"{\"id\": 1}\n"[..], &mut writer),
+            Err(Error::InvalidPointer(ref pointer)) if pointer == "user/name"
+        ));
+        assert_eq!(0, writer.bytes_written());
+    }
+
+    #[test]
+    fn rejects() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let mut rejected = vec![];
+        let mut exceptions = vec![];
+        let summary = JsonConverter::new()
+            .rejected_data(&mut rejected)
+            .exceptions(&mut exceptions)
+            .convert(
+                &b"{\"id\": 1}\n\
+                   {\"id\": 1.5}\n\
+                   {\"name\": \"Wilma\"}\n\
+                   {\"id\": 18446744073709551615}\n\
+                   {\"id\": 5, \"token\": \"!\"}\n\
+                   [6]\n\
+                   {\"id\": 7"[..],
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(
+            Summary {
+                rows: 1,
+                rejected: 6
+            },
+            summary
+        );
+        assert_eq!(
+            "{\"id\": 1.5}\n\
+             {\"name\": \"Wilma\"}\n\
+             {\"id\": 18446744073709551615}\n\
+             {\"id\": 5, \"token\": \"!\"}\n\
+             [6]\n\
+             {\"id\": 7\n",
+            String::from_utf8(rejected).unwrap()
+        );
+        let exceptions = String::from_utf8(exceptions).unwrap();
+        let lines: Vec<&str> = exceptions.lines().collect();
+        assert_eq!(
+            &[
+                "line 2: column `id` (index 0): unable to convert \"INTEGER\" to \"1.5\"",
+                "line 3: column `id` (index 0) is NOT NULL",
+                "line 4: column `id` (index 0): \"18446744073709551615\" overflows \"INTEGER\"",
+                "line 5: column `token` (index 4): unable to parse \"!\" as VARBINARY(20)",
+                "line 6: invalid row: expected a JSON object",
+            ],
+            &lines[..5]
+        );
+        assert!(lines[5].starts_with("line 7: EOF while parsing"));
+    }
+
+    #[test]
+    fn abort_on_error() {
+        let mut writer = VnfWriter::new(vec![], schema());
+        let error = JsonConverter::new()
+            .abort_on_error()
+            .convert(&b"{\"id\": 1}\n{\"id\": \"x\"}\n"[..], &mut writer)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Record { record: 2, ref source } if matches!(**source, Error::Conversion { index: 0, .. })
+        ));
+    }
+}
@@ -1,10 +1,47 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod column;
+#[cfg(any(feature = "csv", feature = "json"))]
+pub mod convert;
+pub mod copy;
+#[cfg(feature = "csv")]
+pub mod csv;
 pub mod date;
+pub mod ddl;
+pub mod error;
 pub mod header;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod numeric;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod reader;
+pub mod record;
 pub mod row;
+pub mod schema;
+#[cfg(feature = "serde")]
+mod ser;
+pub mod text;
 
-use column::{Type, Value};
-use std::io::{Result, Write};
+use column::Value;
+#[cfg(any(feature = "csv", feature = "json"))]
+pub use convert::Summary;
+pub use copy::CopyStatement;
+#[cfg(feature = "csv")]
+pub use csv::CsvConverter;
+pub use ddl::CreateTable;
+pub use error::{Error, Result};
+#[cfg(feature = "json")]
+pub use json::JsonConverter;
+#[cfg(feature = "parquet")]
+pub use parquet::ParquetConverter;
+pub use reader::VnfReader;
+pub use record::VnfRow;
+pub use schema::{Column, Schema};
+use std::io::Write;
+pub use text::TextFormat;
+#[cfg(feature = "derive")]
+pub use vnf_derive::VnfRow;
 
 const BIT_POSITION: [u8; 8] = [
     0b1000_0000,
@@ -37,47 +74,184 @@ fn push_null_value_bit_field(buffer: &mut Vec<u8>, values: &[Value]) {
                 buffer.push(bit)
             } else {
                 let j = buffer.len() - 1;
-                buffer[j] = buffer[j] | bit;
+                buffer[j] |= bit;
             }
         })
 }
 
-fn push_row_data(buffer: &mut Vec<u8>, types: &[Type], values: &[Value]) {
+fn push_row_data(buffer: &mut Vec<u8>, columns: &[Column], values: &[Value]) -> Result<()> {
     values
         .iter()
         .enumerate()
-        .map(|(i, v)| (types[i], v))
-        .for_each(|(t, v)| t.append(buffer, v).unwrap())
+        .try_for_each(|(index, v)| append_column(buffer, index, &columns[index], v))
 }
 
-pub struct VnfWriter<'a> {
-    column_types: &'a [column::Type],
+fn append_column(buffer: &mut Vec<u8>, index: usize, column: &Column, value: &Value) -> Result<()> {
+    if let Value::Null = value {
+        if !column.nullable {
+            return Err(Error::NotNull {
+                index,
+                name: column.name.clone(),
+            });
+        }
+    }
+    column
+        .column_type
+        .append(buffer, value)
+        .map_err(|source| Error::Conversion {
+            index,
+            name: column.name.clone(),
+            source,
+        })
+}
+
+/// Values for `VnfWriter::write_row`, each converted with `Value::from`.
+///
+/// `row![42, true, "Fred", None::<i64>]` is
+/// `&[Value::Integer(42), Value::Boolean(true), Value::VarChar("Fred"), Value::Null]`.
+#[macro_export]
+macro_rules! row {
+    ($($value:expr),* $(,)?) => {
+        &[$($crate::column::Value::from($value)),*]
+    };
+}
+
+/// Builds a row one column at a time, for values that are only available
+/// briefly, such as text parsed into a scratch buffer.
+pub struct RowBuilder<'a> {
+    columns: &'a [Column],
+    buffer: &'a mut Vec<u8>,
+    index: usize,
+}
+
+impl RowBuilder<'_> {
+    /// The column the next value is pushed to, `None` once the row is full.
+    pub fn column(&self) -> Option<&Column> {
+        self.columns.get(self.index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn push(&mut self, value: &Value) -> Result<()> {
+        let index = self.index;
+        let column = self.columns.get(index).ok_or(Error::Arity {
+            expected: self.columns.len(),
+            actual: index + 1,
+        })?;
+        append_column(self.buffer, index, column, value)?;
+        if let Value::Null = value {
+            self.buffer[ROW_LENGTH_LEN + index / 8] |= BIT_POSITION[index % 8];
+        }
+        self.index += 1;
+        Ok(())
+    }
+}
+
+const ROW_LENGTH_LEN: usize = 4;
+
+/// Writes a Vertica Native Format file to a sink.
+///
+/// The file header is written before the first row, or by `finish` if the
+/// file has no rows.
+pub struct VnfWriter<W: Write> {
+    out: W,
+    schema: Schema,
     buffer: Vec<u8>,
+    header_written: bool,
+    bytes_written: u64,
+    record_schema_checked: bool,
 }
 
-impl<'a> VnfWriter<'a> {
-    pub fn new(column_types: &[Type]) -> VnfWriter {
+impl<W: Write> VnfWriter<W> {
+    pub fn new<S: Into<Schema>>(out: W, schema: S) -> VnfWriter<W> {
         VnfWriter {
-            column_types,
+            out,
+            schema: schema.into(),
             buffer: Vec::<u8>::new(),
+            header_written: false,
+            bytes_written: 0,
+            record_schema_checked: false,
         }
     }
 
-    pub fn write_file_header<W: std::io::Write>(&self, out: &mut W) -> Result<usize> {
-        out.write(header::to_header(self.column_types).as_slice())
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    /// Total number of bytes written by this writer, header included.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    fn write_file_header(&mut self) -> Result<usize> {
+        if self.header_written {
+            return Ok(0);
+        }
+        let header = header::to_header(&self.schema.column_types());
+        self.out.write_all(&header)?;
+        self.header_written = true;
+        self.bytes_written += header.len() as u64;
+        Ok(header.len())
     }
 
-    pub fn write_row<'b, W: Write>(&mut self, out: &'b mut W, values: &[Value]) -> Result<usize> {
+    /// Write a row, returning the number of bytes written including the file
+    /// header for the first row.
+    pub fn write_row(&mut self, values: &[Value]) -> Result<usize> {
+        if values.len() != self.schema.len() {
+            return Err(Error::Arity {
+                expected: self.schema.len(),
+                actual: values.len(),
+            });
+        }
+
         self.buffer.clear();
 
         // Skip row data length - we don't know length yet
-        self.buffer.extend_from_slice(&[0, 0, 0, 0]);
+        self.buffer.extend_from_slice(&[0; ROW_LENGTH_LEN]);
 
         push_null_value_bit_field(&mut self.buffer, values);
         let row_header_len = self.buffer.len();
 
-        push_row_data(&mut self.buffer, self.column_types, &values);
+        push_row_data(&mut self.buffer, self.schema.columns(), values)?;
+
+        self.write_buffered_row(row_header_len)
+    }
+
+    /// Write a row built one column at a time. Nothing is written if `build`
+    /// fails or does not push a value for every column.
+    pub fn write_with<F>(&mut self, build: F) -> Result<usize>
+    where
+        F: FnOnce(&mut RowBuilder) -> Result<()>,
+    {
+        // Row data length, then a null value bit field for RowBuilder to set
+        self.buffer.clear();
+        self.buffer
+            .resize(ROW_LENGTH_LEN + self.schema.len().div_ceil(8), 0);
+        let row_header_len = self.buffer.len();
+
+        let mut row = RowBuilder {
+            columns: self.schema.columns(),
+            buffer: &mut self.buffer,
+            index: 0,
+        };
+        build(&mut row)?;
+        if row.index != self.schema.len() {
+            return Err(Error::Arity {
+                expected: self.schema.len(),
+                actual: row.index,
+            });
+        }
 
+        self.write_buffered_row(row_header_len)
+    }
+
+    fn write_buffered_row(&mut self, row_header_len: usize) -> Result<usize> {
         let row_data_len = (self.buffer.len() - row_header_len) as u32;
         row_data_len
             .to_le_bytes()
@@ -85,15 +259,47 @@ impl<'a> VnfWriter<'a> {
             .enumerate()
             .for_each(|(i, b)| self.buffer[i] = *b);
 
-        out.write(&self.buffer)
+        self.write_buffer()
+    }
+
+    /// Write the encoded rows in the buffer, after the file header if this is
+    /// the first write.
+    fn write_buffer(&mut self) -> Result<usize> {
+        let header_len = self.write_file_header()?;
+        self.out.write_all(&self.buffer)?;
+        self.bytes_written += self.buffer.len() as u64;
+        Ok(header_len + self.buffer.len())
+    }
+
+    /// Write the file header if no rows were written, flush, and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.write_file_header()?;
+        self.out.flush()?;
+        Ok(self.out)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
     use column::{Type, Value, Value::*};
-    use std::io::Cursor;
+    use std::io::{self, Cursor};
+
+    /// A sink that accepts at most one byte per call, like a congested pipe.
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.extend(buf.iter().take(1));
+            Ok(buf.len().min(1))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
 
     fn new_null_values(values: &[Value]) -> Vec<u8> {
         let mut buffer = Vec::<u8>::new();
@@ -129,7 +335,7 @@ mod tests {
 
     fn new_row_data(types: &[column::Type], values: &[column::Value]) -> Vec<u8> {
         let mut buf = Vec::<u8>::new();
-        push_row_data(&mut buf, types, values);
+        push_row_data(&mut buf, Schema::from(types).columns(), values).unwrap();
         buf
     }
 
@@ -163,17 +369,15 @@ mod tests {
     #[rustfmt::skip]
     #[test]
     fn write_vnf() {
-        let mut writer = VnfWriter::new(&[
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), &[
             Type::Integer, Type::Boolean, Type::Char { len: 4 }, Type::Boolean,
             Type::Boolean, Type::Boolean, Type::Boolean, Type::Boolean,
             Type::Boolean,
         ]);
-        let mut out: Cursor<Vec<u8>> = Cursor::new(vec![]);
-        writer.write_file_header(&mut out).unwrap();
         writer.write_row(
-            &mut out,
             &[Integer(4), Boolean(true), Char("Fred"), Null, Null, Null, Null, Null, Null],
         ).unwrap();
+        let out = writer.finish().unwrap();
 
         assert_eq!(
             &vec![78, 65, 84, 73, 86, 69, 10, 255, 13, 10, 0, // SIGNATURE
@@ -200,4 +404,174 @@ mod tests {
             out.get_ref()
         )
     }
+
+    #[test]
+    fn write_row_conversion_error() {
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), &[Type::Integer, Type::Boolean]);
+        match writer.write_row(&[Integer(4), Integer(5)]) {
+            Err(Error::Conversion {
+                index,
+                name,
+                source,
+            }) => {
+                assert_eq!(1, index);
+                assert_eq!("column_1", name);
+                assert_eq!(
+                    column::ConversionError::InvalidConversion {
+                        column_type: String::from("BOOLEAN"),
+                        value: String::from("Integer(5)"),
+                    },
+                    source
+                );
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+        assert!(writer.get_ref().get_ref().is_empty());
+
+        writer.write_row(&[Integer(4), Boolean(true)]).unwrap();
+        assert_eq!(28 + 14, writer.finish().unwrap().get_ref().len());
+    }
+
+    #[test]
+    fn write_row_arity() {
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), &[Type::Integer, Type::Boolean]);
+        assert!(matches!(
+            writer.write_row(&[Integer(4)]),
+            Err(Error::Arity {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            writer.write_row(&[Integer(4), Boolean(true), Null]),
+            Err(Error::Arity {
+                expected: 2,
+                actual: 3
+            })
+        ));
+        assert!(writer.get_ref().get_ref().is_empty());
+    }
+
+    #[test]
+    fn write_row_short_writes() {
+        let mut writer = VnfWriter::new(Trickle(vec![]), &[Type::Integer, Type::Char { len: 4 }]);
+        assert_eq!(
+            28 + 17,
+            writer.write_row(&[Integer(4), Char("Fred")]).unwrap()
+        );
+        assert_eq!(17, writer.write_row(&[Integer(5), Char("Tom")]).unwrap());
+        assert_eq!(62, writer.bytes_written());
+        let out = writer.finish().unwrap();
+        assert_eq!(62, out.0.len());
+        assert_eq!(&[0x54, 0x6f, 0x6d, 0x20], &out.0[58..]);
+    }
+
+    #[test]
+    fn finish_without_rows() {
+        let writer = VnfWriter::new(Cursor::new(vec![]), &[Type::Integer]);
+        assert_eq!(
+            header::to_header(&[Type::Integer]),
+            writer.finish().unwrap().into_inner()
+        );
+    }
+
+    #[test]
+    fn write_row_not_null() {
+        let schema: Schema = vec![
+            ("name", Type::VarChar { len: 20 }, true),
+            ("customer_id", Type::Integer, false),
+        ]
+        .into_iter()
+        .collect();
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), schema);
+        writer.write_row(&[Null, Integer(3)]).unwrap();
+        let error = writer.write_row(&[VarChar("Fred"), Null]).unwrap_err();
+        assert_eq!(
+            "column `customer_id` (index 1) is NOT NULL",
+            error.to_string()
+        );
+        assert!(matches!(error, Error::NotNull { index: 1, .. }));
+    }
+
+    #[test]
+    fn write_with() {
+        let types = [Type::Integer, Type::Boolean, Type::Char { len: 4 }];
+        let mut expected = VnfWriter::new(Cursor::new(vec![]), &types);
+        expected
+            .write_row(&[Integer(4), Null, Char("Fred")])
+            .unwrap();
+
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), &types);
+        writer
+            .write_with(|row| {
+                assert_eq!(Some(Type::Integer), row.column().map(|c| c.column_type));
+                row.push(&Integer(4))?;
+                row.push(&Null)?;
+                let name = String::from("Fred");
+                row.push(&Char(&name))
+            })
+            .unwrap();
+        assert_eq!(
+            expected.finish().unwrap().into_inner(),
+            writer.finish().unwrap().into_inner()
+        );
+    }
+
+    #[test]
+    fn write_with_arity() {
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), &[Type::Integer]);
+        assert!(matches!(
+            writer.write_with(|_| Ok(())),
+            Err(Error::Arity {
+                expected: 1,
+                actual: 0
+            })
+        ));
+        assert!(matches!(
+            writer.write_with(|row| {
+                row.push(&Integer(1))?;
+                row.push(&Integer(2))
+            }),
+            Err(Error::Arity {
+                expected: 1,
+                actual: 2
+            })
+        ));
+        assert!(writer.get_ref().get_ref().is_empty());
+    }
+
+    #[test]
+    fn row_macro() {
+        let date = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let values: &[Value] = row![42, true, "Fred", None::<i64>, date, 1.5, Null];
+        assert_eq!(
+            &[
+                Integer(42),
+                Boolean(true),
+                VarChar("Fred"),
+                Null,
+                Date(date),
+                Float(1.5),
+                Null
+            ],
+            values
+        );
+
+        let mut writer = VnfWriter::new(
+            Cursor::new(vec![]),
+            &[Type::Integer, Type::Char { len: 4 }, Type::Boolean],
+        );
+        writer.write_row(row![4, "Fred", Some(false)]).unwrap();
+        let mut expected = VnfWriter::new(
+            Cursor::new(vec![]),
+            &[Type::Integer, Type::Char { len: 4 }, Type::Boolean],
+        );
+        expected
+            .write_row(&[Integer(4), Char("Fred"), Boolean(false)])
+            .unwrap();
+        assert_eq!(
+            expected.finish().unwrap().into_inner(),
+            writer.finish().unwrap().into_inner()
+        );
+    }
 }
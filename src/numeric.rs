@@ -0,0 +1,220 @@
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An exact decimal number, `mantissa * 10^-scale`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseDecimalError {
+    #[error("invalid decimal {0:?}")]
+    Invalid(String),
+    #[error("decimal {0:?} has too many digits")]
+    Overflow(String),
+}
+
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u32) -> Decimal {
+        Decimal { mantissa, scale }
+    }
+
+    /// The mantissa for this value at another scale, rounding half away from
+    /// zero when digits are dropped. `None` if the result overflows an i128.
+    pub fn rescale(&self, scale: u32) -> Option<i128> {
+        if scale >= self.scale {
+            10i128
+                .checked_pow(scale - self.scale)
+                .and_then(|factor| self.mantissa.checked_mul(factor))
+        } else {
+            match 10i128.checked_pow(self.scale - scale) {
+                Some(divisor) => {
+                    let quotient = self.mantissa / divisor;
+                    let remainder = (self.mantissa % divisor).abs();
+                    if remainder >= divisor - remainder {
+                        Some(quotient + self.mantissa.signum())
+                    } else {
+                        Some(quotient)
+                    }
+                }
+                None => Some(0),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            write!(f, "{}{}", sign, digits)
+        } else if digits.len() > scale {
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{}{}.{}", sign, integer, fraction)
+        } else {
+            write!(f, "{}0.{:0>width$}", sign, digits, width = scale)
+        }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseDecimalError::Invalid(s.to_string());
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = match unsigned.find('.') {
+            Some(i) => (&unsigned[..i], &unsigned[i + 1..]),
+            None => (unsigned, ""),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i128 = 0;
+        for c in integer.chars().chain(fraction.chars()) {
+            let digit = c.to_digit(10).ok_or_else(invalid)? as i128;
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit))
+                .ok_or_else(|| ParseDecimalError::Overflow(s.to_string()))?;
+        }
+
+        Ok(Decimal {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fraction.len() as u32,
+        })
+    }
+}
+
+/// Append a scaled NUMERIC as `width` bytes of 64-bit two's complement words,
+/// most significant word first, each word in little endian byte order.
+pub fn append_words(buffer: &mut Vec<u8>, mantissa: i128, width: u32) {
+    let words = (width / 8) as usize;
+    let sign_extension = if mantissa < 0 { u64::MAX } else { 0 };
+    for word in (0..words).rev() {
+        let bits = match word {
+            0 => mantissa as u64,
+            1 => (mantissa >> 64) as u64,
+            _ => sign_extension,
+        };
+        buffer.extend_from_slice(&bits.to_le_bytes());
+    }
+}
+
+/// Read a scaled NUMERIC written by `append_words`. `None` if the value does
+/// not fit in an i128.
+pub fn from_words(bytes: &[u8]) -> Option<i128> {
+    let words: Vec<u64> = bytes
+        .chunks_exact(8)
+        .map(|w| {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(w);
+            u64::from_le_bytes(word)
+        })
+        .collect();
+    match words.as_slice() {
+        [] => None,
+        [low] => Some(*low as i64 as i128),
+        [high @ .., upper, low] => {
+            let mantissa = ((*upper as i128) << 64) | *low as i128;
+            let sign_extension = if mantissa < 0 { u64::MAX } else { 0 };
+            if high.iter().all(|w| *w == sign_extension) {
+                Some(mantissa)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Decimal::new(12345, 2)), "123.45".parse());
+        assert_eq!(Ok(Decimal::new(-1, 3)), "-0.001".parse());
+        assert_eq!(Ok(Decimal::new(7, 0)), "+7".parse());
+        assert_eq!(Ok(Decimal::new(5, 1)), ".5".parse());
+        assert_eq!(Ok(Decimal::new(5, 0)), "5.".parse());
+        assert_eq!(
+            Err(ParseDecimalError::Invalid(String::from("1.2.3"))),
+            "1.2.3".parse::<Decimal>()
+        );
+        assert_eq!(
+            Err(ParseDecimalError::Invalid(String::from("-"))),
+            "-".parse::<Decimal>()
+        );
+        assert!(matches!(
+            "1".repeat(40).parse::<Decimal>(),
+            Err(ParseDecimalError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("123.45", Decimal::new(12345, 2).to_string());
+        assert_eq!("-0.001", Decimal::new(-1, 3).to_string());
+        assert_eq!("42", Decimal::new(42, 0).to_string());
+    }
+
+    #[test]
+    fn rescale() {
+        assert_eq!(Some(1_234_500), Decimal::new(12345, 2).rescale(4));
+        assert_eq!(Some(123), Decimal::new(12345, 2).rescale(0));
+        assert_eq!(Some(124), Decimal::new(12350, 2).rescale(0));
+        assert_eq!(Some(-124), Decimal::new(-12350, 2).rescale(0));
+        assert_eq!(None, Decimal::new(i128::MAX, 0).rescale(1));
+    }
+
+    #[test]
+    fn words() {
+        let mut out = vec![];
+        append_words(&mut out, 1_234_532, 24);
+        assert_eq!(
+            vec![
+                0u8, 0, 0, 0, 0, 0, 0, 0, // word 3
+                0, 0, 0, 0, 0, 0, 0, 0, // word 2
+                0x64, 0xd6, 0x12, 0, 0, 0, 0, 0, // word 1
+            ],
+            out
+        );
+    }
+
+    #[test]
+    fn negative_words() {
+        let mut out = vec![];
+        append_words(&mut out, -2, 16);
+        assert_eq!(
+            vec![
+                0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // word 2
+                0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // word 1
+            ],
+            out
+        );
+    }
+
+    #[test]
+    fn read_words() {
+        for (mantissa, width) in &[(1_234_532, 24), (-2, 16), (-12_500, 8), (i128::MIN, 32)] {
+            let mut out = vec![];
+            append_words(&mut out, *mantissa, *width);
+            assert_eq!(Some(*mantissa), from_words(&out));
+        }
+        let mut out = vec![0u8; 8];
+        out[0] = 1;
+        out.extend_from_slice(&[0u8; 16]);
+        assert_eq!(None, from_words(&out));
+    }
+}
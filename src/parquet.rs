@@ -0,0 +1,283 @@
+use crate::column::Type;
+use crate::error::{Error, Result};
+use crate::schema::Schema;
+use crate::VnfWriter;
+use ::arrow::array::{Array, ArrayRef};
+use ::arrow::compute::{self, CastOptions};
+use ::arrow::datatypes::{DataType, IntervalUnit, TimeUnit};
+use ::arrow::record_batch::RecordBatch;
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use ::parquet::arrow::ProjectionMask;
+use ::parquet::file::reader::ChunkReader;
+use std::io::Write;
+
+/// Converts a Parquet file to VNF a record batch at a time.
+///
+/// The writer's schema picks the Parquet columns by name, so it may be a
+/// subset or reordering of the file's columns. Columns whose Parquet type
+/// differs from the column type are cast, parsing text where needed.
+///
+/// Unlike the CSV and JSON converters nothing is rejected: any error ends
+/// the conversion, after the batches before it were written.
+pub struct ParquetConverter<R: ChunkReader> {
+    builder: ParquetRecordBatchReaderBuilder<R>,
+}
+
+impl<R: ChunkReader + 'static> ParquetConverter<R> {
+    /// Read the metadata of a Parquet file.
+    pub fn new(input: R) -> Result<Self> {
+        Ok(ParquetConverter {
+            builder: ParquetRecordBatchReaderBuilder::try_new(input)?,
+        })
+    }
+
+    /// The schema inferred from the file's columns.
+    pub fn schema(&self) -> Result<Schema> {
+        crate::arrow::schema(self.builder.schema())
+    }
+
+    /// Rows read at a time, 1024 by default.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.builder = self.builder.with_batch_size(batch_size);
+        self
+    }
+
+    /// Only convert these row groups.
+    pub fn row_groups(mut self, row_groups: Vec<usize>) -> Self {
+        self.builder = self.builder.with_row_groups(row_groups);
+        self
+    }
+
+    /// Write every row, returning how many were written. Record numbers in
+    /// errors count from the first row read.
+    pub fn convert<W: Write>(self, writer: &mut VnfWriter<W>) -> Result<u64> {
+        let file_schema = self.builder.schema().clone();
+        let mut roots = Vec::with_capacity(writer.schema().len());
+        for column in writer.schema().columns() {
+            let index = file_schema
+                .index_of(&column.name)
+                .map_err(|_| Error::UnknownColumn(column.name.clone()))?;
+            roots.push(index);
+        }
+        let mask = ProjectionMask::roots(self.builder.parquet_schema(), roots);
+        let reader = self.builder.with_projection(mask).build()?;
+
+        let mut rows = 0;
+        for batch in reader {
+            let batch = cast_batch(&batch?, writer.schema())?;
+            writer.write_batch(&batch).map_err(|e| match e {
+                Error::Record { record, source } => Error::Record {
+                    record: rows + record,
+                    source,
+                },
+                e => e,
+            })?;
+            rows += batch.num_rows() as u64;
+        }
+        Ok(rows)
+    }
+}
+
+/// The columns of a batch in schema order, cast to the column types.
+fn cast_batch(batch: &RecordBatch, schema: &Schema) -> Result<RecordBatch> {
+    if schema.is_empty() {
+        // Building a batch from no columns would lose the row count
+        return Ok(batch.project(&[])?);
+    }
+    let options = CastOptions {
+        safe: false,
+        ..CastOptions::default()
+    };
+    let mut columns: Vec<(&str, ArrayRef)> = Vec::with_capacity(schema.len());
+    for column in schema.columns() {
+        // The projection only has columns the schema names
+        let mut array = batch.column_by_name(&column.name).unwrap().clone();
+        if needs_cast(array.data_type(), &column.column_type) {
+            if let Some(data_type) = data_type(&column.column_type) {
+                array = compute::cast_with_options(&array, &data_type, &options).map_err(|e| {
+                    Error::Field {
+                        name: column.name.clone(),
+                        source: Box::new(e.into()),
+                    }
+                })?;
+            }
+        }
+        columns.push((&column.name, array));
+    }
+    Ok(RecordBatch::try_from_iter(columns)?)
+}
+
+/// Whether values of an Arrow type do not already convert to a column type.
+fn needs_cast(from: &DataType, to: &Type) -> bool {
+    match (crate::arrow::column_type(from), to) {
+        (Ok(Type::VarChar { .. }), Type::Char { .. }) => false,
+        (Ok(Type::VarBinary { .. }), Type::Binary { .. }) => false,
+        (Ok(Type::Binary { .. }), Type::VarBinary { .. } | Type::Binary { .. }) => false,
+        (Ok(from), to) => from != *to,
+        (Err(_), _) => true,
+    }
+}
+
+/// The Arrow type to cast to for a column type, if Arrow has one.
+fn data_type(column_type: &Type) -> Option<DataType> {
+    Some(match column_type {
+        Type::Boolean => DataType::Boolean,
+        Type::Integer => DataType::Int64,
+        Type::Float => DataType::Float64,
+        Type::Char { .. } | Type::VarChar { .. } => DataType::Utf8,
+        Type::Binary { .. } | Type::VarBinary { .. } => DataType::Binary,
+        Type::Date => DataType::Date32,
+        Type::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+        Type::TimestampTz => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        Type::Time => DataType::Time64(TimeUnit::Microsecond),
+        Type::TimeTz => return None,
+        Type::Numeric { precision, scale } if *precision <= 38 => {
+            DataType::Decimal128(*precision as u8, *scale as i8)
+        }
+        Type::Numeric { .. } => return None,
+        Type::Interval => DataType::Duration(TimeUnit::Microsecond),
+        Type::IntervalYearToMonth => DataType::Interval(IntervalUnit::YearMonth),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::Value;
+    use crate::row;
+    use crate::schema::Column;
+    use ::arrow::array::{Int32Array, StringArray};
+    use ::parquet::arrow::ArrowWriter;
+    use ::parquet::file::properties::WriterProperties;
+    use bytes::Bytes;
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+
+    /// A Parquet file with row groups of two rows.
+    fn parquet(ids: Vec<i32>, joined: Vec<Option<&str>>) -> Bytes {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(Int32Array::from(ids)) as ArrayRef),
+            ("joined", Arc::new(StringArray::from(joined)) as ArrayRef),
+        ])
+        .unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut out = vec![];
+        let mut writer = ArrowWriter::try_new(&mut out, batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        Bytes::from(out)
+    }
+
+    #[test]
+    fn convert_inferred_schema() {
+        let input = parquet(vec![1, 2, 3], vec![Some("1999-01-08"), None, Some("x")]);
+        let converter = ParquetConverter::new(input).unwrap().batch_size(2);
+        let schema = converter.schema().unwrap();
+        assert_eq!(
+            vec![Type::Integer, Type::VarChar { len: 65_000 }],
+            schema.column_types()
+        );
+
+        let mut writer = VnfWriter::new(vec![], schema.clone());
+        assert_eq!(3, converter.convert(&mut writer).unwrap());
+
+        let mut expected = VnfWriter::new(vec![], schema);
+        expected.write_row(row![1, "1999-01-08"]).unwrap();
+        expected.write_row(row![2, Value::Null]).unwrap();
+        expected.write_row(row![3, "x"]).unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn convert_given_schema() {
+        let input = parquet(vec![1, 2], vec![Some("1999-01-08"), None]);
+        let schema = Schema::new(vec![
+            Column::new("joined", Type::Date, true),
+            Column::new(
+                "id",
+                Type::Numeric {
+                    precision: 5,
+                    scale: 1,
+                },
+                false,
+            ),
+        ]);
+        let mut writer = VnfWriter::new(vec![], schema.clone());
+        ParquetConverter::new(input)
+            .unwrap()
+            .convert(&mut writer)
+            .unwrap();
+
+        let joined = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let mut expected = VnfWriter::new(vec![], schema);
+        expected
+            .write_row(row![joined, crate::numeric::Decimal::new(10, 1)])
+            .unwrap();
+        expected
+            .write_row(row![Value::Null, crate::numeric::Decimal::new(20, 1)])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn convert_no_columns() {
+        let input = parquet(vec![1, 2, 3], vec![None, None, None]);
+        let mut writer = VnfWriter::new(vec![], Schema::default());
+        assert_eq!(
+            3,
+            ParquetConverter::new(input)
+                .unwrap()
+                .convert(&mut writer)
+                .unwrap()
+        );
+
+        let mut expected = VnfWriter::new(vec![], Schema::default());
+        for _ in 0..3 {
+            expected.write_row(&[]).unwrap();
+        }
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn convert_errors() {
+        let input = parquet(
+            vec![1, 2, 3],
+            vec![Some("1999-01-08"), Some("1999-01-09"), None],
+        );
+        let mut writer = VnfWriter::new(
+            vec![],
+            Schema::new(vec![Column::new("joined", Type::Date, false)]),
+        );
+        let result = ParquetConverter::new(input.clone())
+            .unwrap()
+            .batch_size(2)
+            .convert(&mut writer);
+        assert!(matches!(
+            result,
+            Err(Error::Record { record: 3, ref source }) if matches!(**source, Error::NotNull { .. })
+        ));
+
+        let mut writer = VnfWriter::new(
+            vec![],
+            Schema::new(vec![Column::new("name", Type::VarChar { len: 20 }, true)]),
+        );
+        assert!(matches!(
+            ParquetConverter::new(input.clone())
+                .unwrap()
+                .convert(&mut writer),
+            Err(Error::UnknownColumn(ref name)) if name == "name"
+        ));
+
+        let input = parquet(vec![1, 2, 3], vec![None, None, Some("x")]);
+        let mut writer = VnfWriter::new(
+            vec![],
+            Schema::new(vec![Column::new("joined", Type::Date, true)]),
+        );
+        assert!(matches!(
+            ParquetConverter::new(input).unwrap().convert(&mut writer),
+            Err(Error::Field { ref name, .. }) if name == "joined"
+        ));
+    }
+}
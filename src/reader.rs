@@ -0,0 +1,278 @@
+use crate::error::{Error, Result};
+use crate::header::{SIGNATURE, VERSION};
+use crate::row::{self, Row};
+use crate::schema::Schema;
+use std::io::{ErrorKind, Read};
+
+/// Reads a Vertica Native Format file, validating the header on construction
+/// and the layout of each row as it is read.
+pub struct VnfReader<R: Read> {
+    input: R,
+    column_widths: Vec<u32>,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> VnfReader<R> {
+    pub fn new(mut input: R) -> Result<VnfReader<R>> {
+        let mut signature = [0u8; 11];
+        read_header_field(&mut input, &mut signature)?;
+        if signature != SIGNATURE {
+            return Err(Error::InvalidHeader(format!(
+                "signature {:02x?} is not {:02x?}",
+                signature, SIGNATURE
+            )));
+        }
+
+        let mut header_area_length = [0u8; 4];
+        read_header_field(&mut input, &mut header_area_length)?;
+        let header_area_length = u32::from_le_bytes(header_area_length);
+
+        let mut version = [0u8; 2];
+        read_header_field(&mut input, &mut version)?;
+        if version != VERSION {
+            return Err(Error::InvalidHeader(format!(
+                "unsupported version {}",
+                u16::from_le_bytes(version)
+            )));
+        }
+
+        let mut filler = [0u8; 1];
+        read_header_field(&mut input, &mut filler)?;
+
+        let mut number_of_columns = [0u8; 2];
+        read_header_field(&mut input, &mut number_of_columns)?;
+        let number_of_columns = u16::from_le_bytes(number_of_columns) as u32;
+        if header_area_length != 4 * number_of_columns + 5 {
+            return Err(Error::InvalidHeader(format!(
+                "header area length {} does not match {} columns",
+                header_area_length, number_of_columns
+            )));
+        }
+
+        let mut column_widths = Vec::with_capacity(number_of_columns as usize);
+        for _ in 0..number_of_columns {
+            let mut width = [0u8; 4];
+            read_header_field(&mut input, &mut width)?;
+            column_widths.push(u32::from_le_bytes(width));
+        }
+
+        Ok(VnfReader {
+            input,
+            column_widths,
+            buffer: vec![],
+        })
+    }
+
+    /// Column widths from the file header, `u32::MAX` for variable width.
+    pub fn column_widths(&self) -> &[u32] {
+        &self.column_widths
+    }
+
+    /// Check the file's column widths match the schema's column types.
+    pub fn check_schema(&self, schema: &Schema) -> Result<()> {
+        if schema.len() != self.column_widths.len() {
+            return Err(Error::Arity {
+                expected: self.column_widths.len(),
+                actual: schema.len(),
+            });
+        }
+        for (index, (column, actual)) in
+            schema.columns().iter().zip(&self.column_widths).enumerate()
+        {
+            let expected = u32::from(&column.column_type);
+            if expected != *actual {
+                return Err(Error::ColumnWidth {
+                    index,
+                    expected,
+                    actual: *actual,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the next row, or `None` at the end of the file.
+    pub fn read_row(&mut self) -> Result<Option<Row>> {
+        let mut data_length = [0u8; 4];
+        match read_fully(&mut self.input, &mut data_length)? {
+            0 => return Ok(None),
+            4 => (),
+            _ => return Err(Error::InvalidRow(String::from("truncated row length"))),
+        }
+
+        // The length is untrusted, so the buffer only grows as data arrives
+        let null_bit_field_len = self.column_widths.len().div_ceil(8);
+        let row_len = null_bit_field_len as u64 + u64::from(u32::from_le_bytes(data_length));
+        self.buffer.clear();
+        (&mut self.input)
+            .take(row_len)
+            .read_to_end(&mut self.buffer)?;
+        if self.buffer.len() as u64 != row_len {
+            return Err(Error::InvalidRow(String::from("truncated row")));
+        }
+
+        let (null_bit_field, data) = self.buffer.split_at(null_bit_field_len);
+        let row = Row::new(null_bit_field.to_vec(), data.to_vec());
+        let mut remaining = row.data();
+        for (index, width) in self.column_widths.iter().enumerate() {
+            if !row.is_null(index) {
+                row::take_column(&mut remaining, *width)?;
+            }
+        }
+        if !remaining.is_empty() {
+            return Err(Error::InvalidRow(format!(
+                "row length {} does not match its columns",
+                row.data_length()
+            )));
+        }
+
+        Ok(Some(row))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+}
+
+impl<R: Read> Iterator for VnfReader<R> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_row().transpose()
+    }
+}
+
+fn read_header_field<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<()> {
+    if read_fully(input, buf)? != buf.len() {
+        return Err(Error::InvalidHeader(String::from("truncated header")));
+    }
+    Ok(())
+}
+
+/// Fill `buf` unless the input ends first, returning the number of bytes read.
+fn read_fully<R: Read>(input: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => (),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::{Type, Value};
+    use crate::header::to_header;
+    use crate::VnfWriter;
+    use std::io::Cursor;
+
+    fn write(types: &[Type], rows: &[&[Value]]) -> Vec<u8> {
+        let mut writer = VnfWriter::new(Cursor::new(vec![]), types);
+        for row in rows {
+            writer.write_row(row).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn read_rows() {
+        let types = [Type::Integer, Type::VarChar { len: 20 }, Type::Boolean];
+        let bytes = write(
+            &types,
+            &[
+                &[Value::Integer(4), Value::VarChar("Fred"), Value::Null],
+                &[Value::Null, Value::VarChar(""), Value::Boolean(false)],
+            ],
+        );
+
+        let reader = VnfReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(&[8, u32::MAX, 1], reader.column_widths());
+        let schema = Schema::from(&types);
+        reader.check_schema(&schema).unwrap();
+
+        let rows = reader.collect::<Result<Vec<Row>>>().unwrap();
+        assert_eq!(2, rows.len());
+        assert_eq!(
+            vec![Value::Integer(4), Value::VarChar("Fred"), Value::Null],
+            rows[0].values(&schema).unwrap()
+        );
+        assert_eq!(
+            vec![Value::Null, Value::VarChar(""), Value::Boolean(false)],
+            rows[1].values(&schema).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_signature() {
+        let mut bytes = to_header(&[Type::Integer]);
+        bytes[0] = b'X';
+        assert!(matches!(
+            VnfReader::new(bytes.as_slice()),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_version() {
+        let mut bytes = to_header(&[Type::Integer]);
+        bytes[15] = 2;
+        assert!(matches!(
+            VnfReader::new(bytes.as_slice()),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_header() {
+        let bytes = to_header(&[Type::Integer, Type::Integer]);
+        assert!(matches!(
+            VnfReader::new(&bytes[..bytes.len() - 1]),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn column_width_mismatch() {
+        let bytes = to_header(&[Type::Integer, Type::Char { len: 3 }]);
+        let reader = VnfReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(
+            reader.check_schema(&Schema::from(&[Type::Integer, Type::Char { len: 4 }])),
+            Err(Error::ColumnWidth {
+                index: 1,
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_row() {
+        let bytes = write(&[Type::Integer], &[&[Value::Integer(4)]]);
+        let mut reader = VnfReader::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(reader.read_row(), Err(Error::InvalidRow(_))));
+    }
+
+    #[test]
+    fn corrupt_row_length() {
+        let mut bytes = write(&[Type::Integer], &[&[Value::Integer(4)]]);
+        let row_start = bytes.len() - 13;
+        bytes[row_start..row_start + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = VnfReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(reader.read_row(), Err(Error::InvalidRow(_))));
+    }
+
+    #[test]
+    fn row_length_mismatch() {
+        let mut bytes = write(&[Type::Integer], &[&[Value::Integer(4)]]);
+        let row_start = bytes.len() - 13;
+        bytes[row_start] = 9;
+        bytes.push(0);
+        let mut reader = VnfReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(reader.read_row(), Err(Error::InvalidRow(_))));
+    }
+}
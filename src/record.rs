@@ -0,0 +1,153 @@
+use crate::column::{Type, Value, MAX_VAR_LEN};
+use crate::error::{Error, Result};
+use crate::numeric::Decimal;
+use crate::schema::Schema;
+use crate::{RowBuilder, VnfWriter};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::io::Write;
+
+/// A struct written as a row, one field per column. Usually derived with
+/// `#[derive(VnfRow)]` and the `derive` feature.
+///
+/// Field attributes set the column type where the field's Rust type has no
+/// natural one, or to override it:
+///
+/// - `#[vnf(char(10))]`, `#[vnf(varchar)]`, `#[vnf(varchar(255))]` for text
+///   fields
+/// - `#[vnf(binary(16))]`, `#[vnf(varbinary)]`, `#[vnf(varbinary(16))]` for
+///   byte fields
+/// - `#[vnf(numeric(18, 4))]` for `Decimal` fields
+/// - `#[vnf(rename = "name")]` to name the column differently
+/// - `#[vnf(skip)]` to leave the field out of the row
+///
+/// `Option` fields are nullable columns.
+pub trait VnfRow {
+    fn schema() -> Schema;
+
+    /// Push a value for each column.
+    fn push_columns(&self, row: &mut RowBuilder) -> Result<()>;
+}
+
+impl<W: Write> VnfWriter<W> {
+    /// Write a row from a `VnfRow`, typically a writer created with
+    /// `T::schema()`. The first record written is checked against the
+    /// writer's schema.
+    pub fn write_record<T: VnfRow + ?Sized>(&mut self, record: &T) -> Result<usize> {
+        if !self.record_schema_checked {
+            if self.schema != T::schema() {
+                return Err(Error::SchemaMismatch);
+            }
+            self.record_schema_checked = true;
+        }
+        self.write_with(|row| record.push_columns(row))
+    }
+}
+
+/// A Rust type that can be a `VnfRow` field.
+pub trait Field {
+    /// Whether the column may be NULL.
+    const NULLABLE: bool = false;
+
+    fn to_value(&self) -> Value<'_>;
+}
+
+/// The column type of a field with no type attribute.
+pub trait DefaultType: Field {
+    const TYPE: Type;
+}
+
+/// Fields that can be `CHAR` or `VARCHAR` columns.
+pub trait TextField: Field {}
+
+/// Fields that can be `BINARY` or `VARBINARY` columns.
+pub trait BinaryField: Field {}
+
+/// Fields that can be `NUMERIC` columns.
+pub trait NumericField: Field {}
+
+macro_rules! field {
+    ($($rust:ty => $column_type:expr, |$v:ident| $value:expr;)*) => {
+        $(
+            impl Field for $rust {
+                fn to_value(&self) -> Value<'_> {
+                    let $v = self;
+                    $value
+                }
+            }
+
+            impl DefaultType for $rust {
+                const TYPE: Type = $column_type;
+            }
+        )*
+    };
+}
+
+field! {
+    bool => Type::Boolean, |v| Value::Boolean(*v);
+    i8 => Type::Integer, |v| Value::Integer((*v).into());
+    i16 => Type::Integer, |v| Value::Integer((*v).into());
+    i32 => Type::Integer, |v| Value::Integer((*v).into());
+    i64 => Type::Integer, |v| Value::Integer(*v);
+    u8 => Type::Integer, |v| Value::Integer((*v).into());
+    u16 => Type::Integer, |v| Value::Integer((*v).into());
+    u32 => Type::Integer, |v| Value::Integer((*v).into());
+    f32 => Type::Float, |v| Value::Float((*v).into());
+    f64 => Type::Float, |v| Value::Float(*v);
+    str => Type::VarChar { len: MAX_VAR_LEN }, |v| Value::VarChar(v);
+    String => Type::VarChar { len: MAX_VAR_LEN }, |v| Value::VarChar(v);
+    [u8] => Type::VarBinary { len: MAX_VAR_LEN }, |v| Value::Binary(v);
+    Vec<u8> => Type::VarBinary { len: MAX_VAR_LEN }, |v| Value::Binary(v);
+    NaiveDate => Type::Date, |v| Value::Date(*v);
+    NaiveDateTime => Type::Timestamp, |v| Value::Timestamp(*v);
+    DateTime<FixedOffset> => Type::TimestampTz, |v| Value::TimestampTz(*v);
+    DateTime<Utc> => Type::TimestampTz, |v| Value::timestamp_tz(v);
+    NaiveTime => Type::Time, |v| Value::Time(*v);
+    Duration => Type::Interval, |v| Value::Interval(*v);
+}
+
+impl Field for Decimal {
+    fn to_value(&self) -> Value<'_> {
+        Value::Numeric(*self)
+    }
+}
+
+impl TextField for str {}
+impl TextField for String {}
+impl BinaryField for [u8] {}
+impl BinaryField for Vec<u8> {}
+impl NumericField for Decimal {}
+
+impl<T: Field> Field for Option<T> {
+    const NULLABLE: bool = true;
+
+    fn to_value(&self) -> Value<'_> {
+        match self {
+            Some(v) => v.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: DefaultType> DefaultType for Option<T> {
+    const TYPE: Type = T::TYPE;
+}
+
+impl<T: TextField> TextField for Option<T> {}
+impl<T: BinaryField> BinaryField for Option<T> {}
+impl<T: NumericField> NumericField for Option<T> {}
+
+impl<T: Field + ?Sized> Field for &T {
+    const NULLABLE: bool = T::NULLABLE;
+
+    fn to_value(&self) -> Value<'_> {
+        (**self).to_value()
+    }
+}
+
+impl<T: DefaultType + ?Sized> DefaultType for &T {
+    const TYPE: Type = T::TYPE;
+}
+
+impl<T: TextField + ?Sized> TextField for &T {}
+impl<T: BinaryField + ?Sized> BinaryField for &T {}
+impl<T: NumericField + ?Sized> NumericField for &T {}
@@ -1,5 +1,7 @@
+use crate::column::Value;
+use crate::error::{Error, Result};
+use crate::schema::Schema;
 use std::convert::From;
-use std::u32;
 
 pub struct Row {
     data_length: u32,
@@ -15,6 +17,76 @@ impl Row {
             data,
         }
     }
+
+    pub fn data_length(&self) -> u32 {
+        self.data_length
+    }
+
+    pub fn null_bit_field(&self) -> &[u8] {
+        &self.null_bit_field
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// True if the bit for column `index` is set in the null value bit field.
+    pub fn is_null(&self, index: usize) -> bool {
+        self.null_bit_field
+            .get(index / 8)
+            .is_some_and(|byte| byte & (0b1000_0000 >> (index % 8)) != 0)
+    }
+
+    /// Decode the row data as values of the schema's column types.
+    pub fn values(&self, schema: &Schema) -> Result<Vec<Value<'_>>> {
+        let mut values = Vec::with_capacity(schema.len());
+        let mut data = self.data.as_slice();
+        for (index, column) in schema.columns().iter().enumerate() {
+            if self.is_null(index) {
+                values.push(Value::Null);
+                continue;
+            }
+            let bytes = take_column(&mut data, u32::from(&column.column_type))?;
+            values.push(
+                column
+                    .column_type
+                    .decode(bytes)
+                    .map_err(|source| Error::Conversion {
+                        index,
+                        name: column.name.clone(),
+                        source,
+                    })?,
+            );
+        }
+        if !data.is_empty() {
+            return Err(Error::InvalidRow(format!(
+                "{} bytes of row data left over",
+                data.len()
+            )));
+        }
+        Ok(values)
+    }
+}
+
+/// Split the next column's bytes off the front of `data`. A width of
+/// `u32::MAX` is a variable width column preceded by its 4 byte length.
+pub(crate) fn take_column<'a>(data: &mut &'a [u8], width: u32) -> Result<&'a [u8]> {
+    let len = if width == u32::MAX {
+        if data.len() < 4 {
+            return Err(Error::InvalidRow(String::from("truncated column length")));
+        }
+        let (prefix, rest) = data.split_at(4);
+        *data = rest;
+        u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize
+    } else {
+        width as usize
+    };
+    if data.len() < len {
+        return Err(Error::InvalidRow(String::from("truncated column data")));
+    }
+    let (column, rest) = data.split_at(len);
+    *data = rest;
+    Ok(column)
 }
 
 impl From<Row> for Vec<u8> {
@@ -30,6 +102,7 @@ impl From<Row> for Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::column::Type;
 
     #[test]
     fn write_row() {
@@ -42,11 +115,37 @@ mod tests {
 
         let row = Row::new(
             vec![128],
-            vec![
-                255, 5, 0, 0, 0, 'h' as u8, 'e' as u8, 'l' as u8, 'l' as u8, 'o' as u8,
-            ],
+            vec![255, 5, 0, 0, 0, b'h', b'e', b'l', b'l', b'o'],
         );
 
         assert_eq!(expected, Vec::from(row));
     }
+
+    #[test]
+    fn values() {
+        let row = Row::new(
+            vec![0b0100_0000],
+            vec![1, 5, 0, 0, 0, b'h', b'e', b'l', b'l', b'o'],
+        );
+        assert!(!row.is_null(0));
+        assert!(row.is_null(1));
+        assert_eq!(
+            vec![Value::Boolean(true), Value::Null, Value::VarChar("hello")],
+            row.values(&Schema::from(&[
+                Type::Boolean,
+                Type::Integer,
+                Type::VarChar { len: 20 }
+            ]))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn values_truncated() {
+        let row = Row::new(vec![0], vec![1, 5, 0, 0, 0, b'h']);
+        assert!(matches!(
+            row.values(&Schema::from(&[Type::Boolean, Type::VarChar { len: 20 }])),
+            Err(Error::InvalidRow(_))
+        ));
+    }
 }
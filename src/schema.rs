@@ -0,0 +1,288 @@
+use crate::column::{ParseTypeError, Type};
+use std::iter::FromIterator;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub column_type: Type,
+    pub nullable: bool,
+}
+
+impl Column {
+    pub fn new<S: Into<String>>(name: S, column_type: Type, nullable: bool) -> Column {
+        Column {
+            name: name.into(),
+            column_type,
+            nullable,
+        }
+    }
+}
+
+/// The named, typed columns of a VNF file and the table it loads into.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+    columns: Vec<Column>,
+}
+
+impl Schema {
+    pub fn new(columns: Vec<Column>) -> Schema {
+        Schema { columns }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn column_types(&self) -> Vec<Type> {
+        self.columns.iter().map(|c| c.column_type).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Position of the column with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c.name == name)
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, Type, bool)> for Schema {
+    fn from_iter<I: IntoIterator<Item = (S, Type, bool)>>(iter: I) -> Self {
+        Schema::new(
+            iter.into_iter()
+                .map(|(name, column_type, nullable)| Column::new(name, column_type, nullable))
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<Column>> for Schema {
+    fn from(columns: Vec<Column>) -> Self {
+        Schema::new(columns)
+    }
+}
+
+/// Nullable columns named `column_0`, `column_1`, ... for bare column types.
+impl From<&[Type]> for Schema {
+    fn from(types: &[Type]) -> Self {
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (format!("column_{}", i), *t, true))
+            .collect()
+    }
+}
+
+impl<const N: usize> From<&[Type; N]> for Schema {
+    fn from(types: &[Type; N]) -> Self {
+        Schema::from(&types[..])
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseSchemaError {
+    #[error("line {line}: missing column type")]
+    MissingType { line: usize },
+    #[error("line {line}: unterminated quoted column name")]
+    UnterminatedName { line: usize },
+    #[error("line {line}: {source}")]
+    Type { line: usize, source: ParseTypeError },
+}
+
+/// Parse a schema from lines of `name TYPE [NOT NULL]`, such as the column
+/// list of a `CREATE TABLE` statement. Names may be double quoted, a
+/// trailing comma is ignored, and blank lines and `--` comments are skipped.
+impl FromStr for Schema {
+    type Err = ParseSchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut columns = vec![];
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let line = strip_comment(line).trim().trim_end_matches(',').trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, rest) = match line.strip_prefix('"') {
+                Some(quoted) => parse_quoted_name(quoted)
+                    .ok_or(ParseSchemaError::UnterminatedName { line: line_number })?,
+                None => {
+                    let end = line.find(char::is_whitespace).unwrap_or(line.len());
+                    (line[..end].to_string(), &line[end..])
+                }
+            };
+
+            // Leading space so a bare `NOT NULL` is not taken as the type
+            let mut column_type = rest.trim();
+            let mut nullable = true;
+            let upper = format!(" {}", column_type.to_uppercase());
+            if upper.ends_with(" NOT NULL") {
+                column_type = column_type[..column_type.len() - "NOT NULL".len()].trim_end();
+                nullable = false;
+            } else if upper.ends_with(" NULL") {
+                column_type = column_type[..column_type.len() - "NULL".len()].trim_end();
+            }
+            if column_type.is_empty() {
+                return Err(ParseSchemaError::MissingType { line: line_number });
+            }
+            let column_type = column_type
+                .parse()
+                .map_err(|source| ParseSchemaError::Type {
+                    line: line_number,
+                    source,
+                })?;
+            columns.push(Column::new(name, column_type, nullable));
+        }
+        Ok(Schema::new(columns))
+    }
+}
+
+/// The line before any `--` comment outside double quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            // A doubled quote inside a name toggles twice
+            '"' => quoted = !quoted,
+            '-' if !quoted && line[i + 1..].starts_with('-') => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+/// Split a double quoted name, after the opening quote, from the rest of the
+/// line. Embedded quotes are doubled.
+fn parse_quoted_name(quoted: &str) -> Option<(String, &str)> {
+    let mut name = String::new();
+    let mut chars = quoted.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '"' {
+            if let Some((_, '"')) = chars.peek() {
+                chars.next();
+            } else {
+                return Some((name, &quoted[i + 1..]));
+            }
+        }
+        name.push(c);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_entries() {
+        let schema: Schema = vec![
+            ("id", Type::Integer, false),
+            ("name", Type::VarChar { len: 20 }, true),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(2, schema.len());
+        assert_eq!(Some(1), schema.index_of("name"));
+        assert_eq!(None, schema.index_of("missing"));
+        assert_eq!(
+            vec![Type::Integer, Type::VarChar { len: 20 }],
+            schema.column_types()
+        );
+        assert_eq!(
+            &Column::new("id", Type::Integer, false),
+            &schema.columns()[0]
+        );
+    }
+
+    #[test]
+    fn from_types() {
+        let schema = Schema::from(&[Type::Integer, Type::Boolean]);
+        assert_eq!(
+            &[
+                Column::new("column_0", Type::Integer, true),
+                Column::new("column_1", Type::Boolean, true),
+            ],
+            schema.columns()
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let schema: Schema = "-- customers\n\
+                              id INTEGER NOT NULL,\n\
+                              \n\
+                              \"full \"\"name\"\"\" varchar(20) null\n\
+                              balance NUMERIC(18,4) -- dollars\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            &[
+                Column::new("id", Type::Integer, false),
+                Column::new("full \"name\"", Type::VarChar { len: 20 }, true),
+                Column::new(
+                    "balance",
+                    Type::Numeric {
+                        precision: 18,
+                        scale: 4
+                    },
+                    true
+                ),
+            ],
+            schema.columns()
+        );
+    }
+
+    #[test]
+    fn parse_quoted_dashes() {
+        let schema: Schema = "\"a--b\" INTEGER -- note\n\"x\"\"--\" DATE\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            &[
+                Column::new("a--b", Type::Integer, true),
+                Column::new("x\"--", Type::Date, true),
+            ],
+            schema.columns()
+        );
+    }
+
+    #[test]
+    fn parse_ddl() {
+        let schema: Schema = vec![
+            ("id", Type::Integer, false),
+            ("at", Type::TimestampTz, true),
+        ]
+        .into_iter()
+        .collect();
+        let ddl = crate::ddl::CreateTable::new("t", &schema).to_string();
+        let columns = &ddl[ddl.find('(').unwrap() + 1..ddl.rfind(')').unwrap()];
+        assert_eq!(schema, columns.parse().unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(ParseSchemaError::MissingType { line: 2 }),
+            "id INTEGER\nname NOT NULL".parse::<Schema>()
+        );
+        assert_eq!(
+            Err(ParseSchemaError::UnterminatedName { line: 1 }),
+            "\"id INTEGER".parse::<Schema>()
+        );
+        assert_eq!(
+            Err(ParseSchemaError::Type {
+                line: 1,
+                source: ParseTypeError::Unknown(String::from("TEXT"))
+            }),
+            "id TEXT".parse::<Schema>()
+        );
+    }
+}
@@ -0,0 +1,798 @@
+use crate::column::{ConversionError, Type, Value};
+use crate::error::{Error, Result};
+use crate::numeric::Decimal;
+use crate::text::TextFormat;
+use crate::{RowBuilder, VnfWriter};
+use serde::ser::{self, Impossible, Serialize};
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::io::Write;
+
+impl<W: Write> VnfWriter<W> {
+    /// Write a struct, tuple or sequence as a row, one field per column in
+    /// schema order. Struct field names must match the column names.
+    ///
+    /// `Option` fields write NULL for `None`. Strings written to non-text
+    /// columns are parsed with the default `TextFormat`, which covers the
+    /// serialized forms of chrono's dates and times.
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, row: &T) -> Result<usize> {
+        let format = TextFormat::default();
+        self.write_with(|row_builder| {
+            row.serialize(RowSerializer {
+                row: row_builder,
+                format: &format,
+            })
+        })
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Serialize(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::Serialize(format!("{} cannot be written as a row", what))
+}
+
+/// Serializes the fields of a struct, tuple or sequence as the columns of a
+/// row.
+struct RowSerializer<'r, 'a> {
+    row: &'r mut RowBuilder<'a>,
+    format: &'r TextFormat,
+}
+
+impl<'r, 'a> RowSerializer<'r, 'a> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(ValueSerializer {
+            row: self.row,
+            format: self.format,
+        })
+    }
+
+    fn push_field<T: Serialize + ?Sized>(&mut self, name: &'static str, value: &T) -> Result<()> {
+        let result = match self.row.column() {
+            Some(column) if column.name != name => Err(Error::Serialize(format!(
+                "column index {} is named `{}`",
+                self.row.index(),
+                column.name
+            ))),
+            _ => self.push(value),
+        };
+        result.map_err(|source| Error::Field {
+            name: name.to_string(),
+            source: Box::new(source),
+        })
+    }
+}
+
+macro_rules! unsupported_row {
+    ($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ok> {
+                Err(unsupported(
+                    &stringify!($method).trim_start_matches("serialize_").replace('_', " "),
+                ))
+            }
+        )*
+    };
+}
+
+impl<'r, 'a> ser::Serializer for RowSerializer<'r, 'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    unsupported_row! {
+        serialize_bool(bool) -> ();
+        serialize_i8(i8) -> ();
+        serialize_i16(i16) -> ();
+        serialize_i32(i32) -> ();
+        serialize_i64(i64) -> ();
+        serialize_u8(u8) -> ();
+        serialize_u16(u16) -> ();
+        serialize_u32(u32) -> ();
+        serialize_u64(u64) -> ();
+        serialize_f32(f32) -> ();
+        serialize_f64(f64) -> ();
+        serialize_char(char) -> ();
+        serialize_str(&str) -> ();
+        serialize_bytes(&[u8]) -> ();
+        serialize_none() -> ();
+        serialize_unit() -> ();
+        serialize_unit_struct(&'static str) -> ();
+        serialize_unit_variant(&'static str, u32, &'static str) -> ();
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(unsupported("newtype variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for RowSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for RowSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for RowSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for RowSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<()> {
+        Err(Error::Field {
+            name: key.to_string(),
+            source: Box::new(Error::Serialize(String::from(
+                "skipped fields would misalign the columns",
+            ))),
+        })
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Serializes a single field as the value of the next column, converting
+/// it to the column's type.
+struct ValueSerializer<'r, 'a> {
+    row: &'r mut RowBuilder<'a>,
+    format: &'r TextFormat,
+}
+
+impl ValueSerializer<'_, '_> {
+    fn column_type(&self) -> Result<Type> {
+        match self.row.column() {
+            Some(column) => Ok(column.column_type),
+            None => Err(Error::Arity {
+                expected: self.row.index(),
+                actual: self.row.index() + 1,
+            }),
+        }
+    }
+
+    fn push(self, value: Value) -> Result<()> {
+        self.row.push(&value)
+    }
+
+    /// Push a value converted to the column type, or report it with the
+    /// column's name.
+    fn push_converted(self, value: std::result::Result<Value, ConversionError>) -> Result<()> {
+        match value {
+            Ok(value) => self.push(value),
+            Err(source) => {
+                let index = self.row.index();
+                let name = self
+                    .row
+                    .column()
+                    .map(|c| c.name.clone())
+                    .unwrap_or_default();
+                Err(Error::Conversion {
+                    index,
+                    name,
+                    source,
+                })
+            }
+        }
+    }
+
+    fn push_integer(self, i: i128) -> Result<()> {
+        let column_type = self.column_type()?;
+        let overflow = || ConversionError::Overflow {
+            column_type: column_type.to_string(),
+            value: i.to_string(),
+        };
+        let value = match column_type {
+            Type::Integer => i64::try_from(i).map(Value::Integer).map_err(|_| overflow()),
+            Type::IntervalYearToMonth => i64::try_from(i)
+                .map(Value::IntervalYearToMonth)
+                .map_err(|_| overflow()),
+            Type::Float => Ok(Value::Float(i as f64)),
+            Type::Numeric { .. } => Ok(Value::Numeric(Decimal::new(i, 0))),
+            _ => Err(ConversionError::InvalidConversion {
+                column_type: column_type.to_string(),
+                value: i.to_string(),
+            }),
+        };
+        self.push_converted(value)
+    }
+
+    fn push_float(self, f: f64) -> Result<()> {
+        let column_type = self.column_type()?;
+        let value = match column_type {
+            Type::Float => Ok(Value::Float(f)),
+            Type::Numeric { .. } => f.to_string().parse().map(Value::Numeric).map_err(|_| {
+                ConversionError::InvalidConversion {
+                    column_type: column_type.to_string(),
+                    value: f.to_string(),
+                }
+            }),
+            _ => Err(ConversionError::InvalidConversion {
+                column_type: column_type.to_string(),
+                value: f.to_string(),
+            }),
+        };
+        self.push_converted(value)
+    }
+}
+
+impl<'r, 'a> ser::Serializer for ValueSerializer<'r, 'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = BytesSerializer<'r, 'a>;
+    type SerializeTuple = BytesSerializer<'r, 'a>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.push(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.push_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.push_integer(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.push_float(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.push_float(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        let column_type = self.column_type()?;
+        let mut scratch = vec![];
+        let value = match column_type {
+            Type::Char { .. } => Ok(Value::Char(v)),
+            Type::VarChar { .. } => Ok(Value::VarChar(v)),
+            _ => self.format.parse(&column_type, v, &mut scratch),
+        };
+        self.push_converted(value)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.push(Value::Binary(v))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.push(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.push(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.push(Value::Null)
+    }
+
+    /// Unit enum variants are written as their names.
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(unsupported("a newtype variant column"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        BytesSerializer::new(self, len.unwrap_or(0), "a sequence column")
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        BytesSerializer::new(self, len, "a tuple column")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("a tuple struct column"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("a tuple variant column"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported("a map column"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(unsupported("a struct column"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("a struct variant column"))
+    }
+}
+
+/// Collects a sequence of bytes, such as a `Vec<u8>`, into the value of a
+/// binary column.
+struct BytesSerializer<'r, 'a> {
+    value: ValueSerializer<'r, 'a>,
+    bytes: Vec<u8>,
+}
+
+impl<'r, 'a> BytesSerializer<'r, 'a> {
+    fn new(value: ValueSerializer<'r, 'a>, len: usize, what: &str) -> Result<Self> {
+        match value.column_type()? {
+            Type::Binary { .. } | Type::VarBinary { .. } => Ok(BytesSerializer {
+                value,
+                bytes: Vec::with_capacity(len),
+            }),
+            _ => Err(unsupported(what)),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.bytes.push(value.serialize(ByteSerializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for BytesSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.value.push(Value::Binary(&self.bytes))
+    }
+}
+
+impl ser::SerializeTuple for BytesSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.value.push(Value::Binary(&self.bytes))
+    }
+}
+
+fn not_a_byte() -> Error {
+    Error::Serialize(String::from(
+        "binary columns can only be written from a sequence of u8",
+    ))
+}
+
+macro_rules! not_a_byte {
+    ($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ok> {
+                Err(not_a_byte())
+            }
+        )*
+    };
+}
+
+/// Serializes an element of a binary column's sequence.
+struct ByteSerializer;
+
+impl ser::Serializer for ByteSerializer {
+    type Ok = u8;
+    type Error = Error;
+    type SerializeSeq = Impossible<u8, Error>;
+    type SerializeTuple = Impossible<u8, Error>;
+    type SerializeTupleStruct = Impossible<u8, Error>;
+    type SerializeTupleVariant = Impossible<u8, Error>;
+    type SerializeMap = Impossible<u8, Error>;
+    type SerializeStruct = Impossible<u8, Error>;
+    type SerializeStructVariant = Impossible<u8, Error>;
+
+    fn serialize_u8(self, v: u8) -> Result<u8> {
+        Ok(v)
+    }
+
+    not_a_byte! {
+        serialize_bool(bool) -> u8;
+        serialize_i8(i8) -> u8;
+        serialize_i16(i16) -> u8;
+        serialize_i32(i32) -> u8;
+        serialize_i64(i64) -> u8;
+        serialize_u16(u16) -> u8;
+        serialize_u32(u32) -> u8;
+        serialize_u64(u64) -> u8;
+        serialize_f32(f32) -> u8;
+        serialize_f64(f64) -> u8;
+        serialize_char(char) -> u8;
+        serialize_str(&str) -> u8;
+        serialize_bytes(&[u8]) -> u8;
+        serialize_none() -> u8;
+        serialize_unit() -> u8;
+        serialize_unit_struct(&'static str) -> u8;
+        serialize_unit_variant(&'static str, u32, &'static str) -> u8;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<u8> {
+        Err(not_a_byte())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<u8> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u8> {
+        Err(not_a_byte())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use chrono::{NaiveDate, NaiveDateTime};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Customer<'a> {
+        id: u32,
+        name: &'a str,
+        active: Option<bool>,
+        joined: NaiveDate,
+        last_seen: Option<NaiveDateTime>,
+        balance: f64,
+        level: Level,
+        token: Vec<u8>,
+    }
+
+    #[derive(Serialize)]
+    enum Level {
+        Gold,
+    }
+
+    fn schema() -> Schema {
+        vec![
+            ("id", Type::Integer, false),
+            ("name", Type::VarChar { len: 20 }, false),
+            ("active", Type::Boolean, true),
+            ("joined", Type::Date, true),
+            ("last_seen", Type::Timestamp, true),
+            (
+                "balance",
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4,
+                },
+                true,
+            ),
+            ("level", Type::Char { len: 8 }, true),
+            ("token", Type::VarBinary { len: 20 }, true),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn serialize_struct() {
+        let joined = NaiveDate::from_ymd_opt(1999, 1, 8).unwrap();
+        let last_seen = joined.and_hms_micro_opt(12, 4, 37, 500_000).unwrap();
+        let mut writer = VnfWriter::new(vec![], schema());
+        writer
+            .serialize(&Customer {
+                id: 7,
+                name: "Fred",
+                active: None,
+                joined,
+                last_seen: Some(last_seen),
+                balance: 12.5,
+                level: Level::Gold,
+                token: vec![0xab, 0xcd],
+            })
+            .unwrap();
+
+        let mut expected = VnfWriter::new(vec![], schema());
+        expected
+            .write_row(&[
+                Value::Integer(7),
+                Value::VarChar("Fred"),
+                Value::Null,
+                Value::Date(joined),
+                Value::Timestamp(last_seen),
+                Value::Numeric(Decimal::new(125, 1)),
+                Value::Char("Gold"),
+                Value::Binary(&[0xab, 0xcd]),
+            ])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn serialize_tuple() {
+        let mut writer = VnfWriter::new(vec![], &[Type::Integer, Type::Float, Type::Date]);
+        writer.serialize(&(1u8, 2, "2020-02-29")).unwrap();
+
+        let mut expected = VnfWriter::new(vec![], &[Type::Integer, Type::Float, Type::Date]);
+        expected
+            .write_row(&[
+                Value::Integer(1),
+                Value::Float(2.0),
+                Value::Date(NaiveDate::from_ymd_opt(2020, 2, 29).unwrap()),
+            ])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+    }
+
+    #[test]
+    fn field_errors() {
+        #[derive(Serialize)]
+        struct Row {
+            id: u64,
+            joined: &'static str,
+        }
+        let schema: Schema = vec![("id", Type::Integer, true), ("joined", Type::Date, true)]
+            .into_iter()
+            .collect();
+        let mut writer = VnfWriter::new(vec![], schema);
+        assert_eq!(
+            "field `id`: column `id` (index 0): \"18446744073709551615\" overflows \"INTEGER\"",
+            writer
+                .serialize(&Row {
+                    id: u64::MAX,
+                    joined: "2020-01-01",
+                })
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "field `joined`: column `joined` (index 1): unable to parse \"yesterday\" as DATE",
+            writer
+                .serialize(&Row {
+                    id: 1,
+                    joined: "yesterday",
+                })
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(0, writer.bytes_written());
+
+        #[derive(Serialize)]
+        struct Renamed {
+            id: u64,
+            joined_on: &'static str,
+        }
+        assert_eq!(
+            "field `joined_on`: column index 1 is named `joined`",
+            writer
+                .serialize(&Renamed {
+                    id: 1,
+                    joined_on: "2020-01-01",
+                })
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn serialize_bytes() {
+        let types = [Type::Binary { len: 2 }, Type::VarBinary { len: 20 }];
+        let mut writer = VnfWriter::new(vec![], &types);
+        writer
+            .serialize(&([0xabu8, 0xcd], vec![1u8, 2, 3]))
+            .unwrap();
+
+        let mut expected = VnfWriter::new(vec![], &types);
+        expected
+            .write_row(&[Value::Binary(&[0xab, 0xcd]), Value::Binary(&[1, 2, 3])])
+            .unwrap();
+        assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+
+        let mut writer = VnfWriter::new(vec![], &[Type::Integer, Type::VarBinary { len: 20 }]);
+        assert_eq!(
+            "a sequence column cannot be written as a row",
+            writer.serialize(&(vec![1u8], 1)).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "binary columns can only be written from a sequence of u8",
+            writer.serialize(&(1, vec![1u16])).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn arity() {
+        let mut writer = VnfWriter::new(vec![], &[Type::Integer, Type::Integer]);
+        assert!(matches!(
+            writer.serialize(&(1, 2, 3)),
+            Err(Error::Arity {
+                expected: 2,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            writer.serialize(&[1]),
+            Err(Error::Arity {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert_eq!(
+            "bool cannot be written as a row",
+            writer.serialize(&true).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::column::{ConversionError, Type, Value};
+use crate::numeric::Decimal;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::convert::TryInto;
+
+/// How text fields, such as those in CSV files, are parsed into values.
+///
+/// Dates and times are tried against each format in turn, using chrono's
+/// `strftime` syntax. Binary columns are hex, optionally prefixed by `0x` or
+/// `\x`.
+#[derive(Clone, Debug)]
+pub struct TextFormat {
+    /// Fields that load as NULL, the empty string by default.
+    pub null_values: Vec<String>,
+    pub date_formats: Vec<String>,
+    pub time_formats: Vec<String>,
+    pub timestamp_formats: Vec<String>,
+    /// Formats for TIMESTAMPTZ, which must include an offset, `%#z` or `%:z`.
+    pub timestamp_tz_formats: Vec<String>,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        let strings = |formats: &[&str]| formats.iter().map(|f| f.to_string()).collect();
+        TextFormat {
+            null_values: strings(&[""]),
+            date_formats: strings(&["%Y-%m-%d"]),
+            time_formats: strings(&["%H:%M:%S%.f"]),
+            timestamp_formats: strings(&["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]),
+            timestamp_tz_formats: strings(&[
+                "%Y-%m-%d %H:%M:%S%.f%#z",
+                "%Y-%m-%dT%H:%M:%S%.f%#z",
+                "%Y-%m-%dT%H:%M:%S%.fZ",
+            ]),
+        }
+    }
+}
+
+const TRUE_VALUES: [&str; 6] = ["t", "true", "y", "yes", "on", "1"];
+const FALSE_VALUES: [&str; 6] = ["f", "false", "n", "no", "off", "0"];
+
+impl TextFormat {
+    /// Use only this date format.
+    pub fn date_format(mut self, format: &str) -> Self {
+        self.date_formats = vec![format.to_string()];
+        self
+    }
+
+    /// Use only this timestamp format.
+    pub fn timestamp_format(mut self, format: &str) -> Self {
+        self.timestamp_formats = vec![format.to_string()];
+        self
+    }
+
+    /// Parse text as a value of a column type. `scratch` holds decoded bytes
+    /// for binary columns.
+    pub fn parse<'a>(
+        &self,
+        column_type: &Type,
+        text: &'a str,
+        scratch: &'a mut Vec<u8>,
+    ) -> Result<Value<'a>, ConversionError> {
+        if self.null_values.iter().any(|null| null == text) {
+            return Ok(Value::Null);
+        }
+
+        let invalid = || ConversionError::InvalidText {
+            column_type: column_type.to_string(),
+            text: text.to_string(),
+        };
+        let trimmed = text.trim();
+
+        Ok(match column_type {
+            Type::Boolean => {
+                if TRUE_VALUES.iter().any(|t| t.eq_ignore_ascii_case(trimmed)) {
+                    Value::Boolean(true)
+                } else if FALSE_VALUES.iter().any(|f| f.eq_ignore_ascii_case(trimmed)) {
+                    Value::Boolean(false)
+                } else {
+                    return Err(invalid());
+                }
+            }
+            Type::Integer => Value::Integer(trimmed.parse().map_err(|_| invalid())?),
+            Type::Float => Value::Float(trimmed.parse().map_err(|_| invalid())?),
+            Type::Char { .. } => Value::Char(text),
+            Type::VarChar { .. } => Value::VarChar(text),
+            Type::Binary { .. } | Type::VarBinary { .. } => {
+                decode_hex(trimmed, scratch).ok_or_else(invalid)?;
+                Value::Binary(scratch)
+            }
+            Type::Date => Value::Date(
+                self.date_formats
+                    .iter()
+                    .find_map(|f| NaiveDate::parse_from_str(trimmed, f).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Type::Timestamp => Value::Timestamp(
+                self.timestamp_formats
+                    .iter()
+                    .find_map(|f| NaiveDateTime::parse_from_str(trimmed, f).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Type::TimestampTz => Value::timestamp_tz(
+                &self
+                    .timestamp_tz_formats
+                    .iter()
+                    .find_map(|f| DateTime::parse_from_str(trimmed, f).ok())
+                    .or_else(|| DateTime::parse_from_rfc3339(trimmed).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Type::Time => Value::Time(
+                self.time_formats
+                    .iter()
+                    .find_map(|f| NaiveTime::parse_from_str(trimmed, f).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            Type::TimeTz => {
+                // Parse the time with a dummy date so chrono reads the offset
+                let timestamp = self
+                    .time_formats
+                    .iter()
+                    .find_map(|f| {
+                        DateTime::parse_from_str(
+                            &format!("2000-01-01 {}", trimmed),
+                            &format!("%Y-%m-%d {}%#z", f),
+                        )
+                        .ok()
+                    })
+                    .ok_or_else(invalid)?;
+                Value::TimeTz(timestamp.time(), *timestamp.offset())
+            }
+            Type::Numeric { .. } => {
+                Value::Numeric(trimmed.parse::<Decimal>().map_err(|_| invalid())?)
+            }
+            Type::Interval => Value::Interval(parse_interval(trimmed).ok_or_else(invalid)?),
+            Type::IntervalYearToMonth => {
+                Value::IntervalYearToMonth(parse_year_to_month(trimmed).ok_or_else(invalid)?)
+            }
+        })
+    }
+}
+
+/// Decode hex digits, with an optional `0x` or `\x` prefix.
+pub fn decode_hex(text: &str, out: &mut Vec<u8>) -> Option<()> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("\\x"))
+        .unwrap_or(text)
+        .as_bytes();
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    out.clear();
+    for pair in digits.chunks(2) {
+        let high = (pair[0] as char).to_digit(16)?;
+        let low = (pair[1] as char).to_digit(16)?;
+        out.push((high * 16 + low) as u8);
+    }
+    Some(())
+}
+
+/// Parse a day-time interval, `[-][days ]HH:MM[:SS[.ffffff]]` or a number of
+/// seconds.
+fn parse_interval(text: &str) -> Option<Duration> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (days, clock) = match unsigned.find(' ') {
+        Some(i) => (unsigned[..i].parse::<i64>().ok()?, unsigned[i + 1..].trim()),
+        None => (0, unsigned),
+    };
+
+    let seconds_to_duration = |seconds: &str| -> Option<Duration> {
+        let seconds = seconds.parse::<Decimal>().ok()?;
+        if seconds.mantissa < 0 {
+            return None;
+        }
+        Some(Duration::microseconds(seconds.rescale(6)?.try_into().ok()?))
+    };
+    let mut parts = clock.split(':');
+    let duration = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(seconds), None, None, None) if days == 0 => seconds_to_duration(seconds)?,
+        (Some(hours), Some(minutes), seconds, None) => {
+            let seconds = match seconds {
+                Some(seconds) => seconds_to_duration(seconds)?,
+                None => Duration::zero(),
+            };
+            Duration::try_hours(hours.parse().ok()?)?
+                .checked_add(&Duration::try_minutes(minutes.parse().ok()?)?)?
+                .checked_add(&seconds)?
+        }
+        _ => return None,
+    };
+    let duration = Duration::try_days(days)?.checked_add(&duration)?;
+    Some(if negative { -duration } else { duration })
+}
+
+/// Parse a year-month interval, `[-]Y-M` or a number of months.
+fn parse_year_to_month(text: &str) -> Option<i64> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    // Only the whole interval is signed
+    let digits = |text: &str| -> Option<i64> {
+        if text.starts_with(['-', '+']) {
+            return None;
+        }
+        text.parse().ok()
+    };
+    let months = match unsigned.find('-') {
+        Some(i) => {
+            let years = digits(&unsigned[..i])?;
+            let months = digits(&unsigned[i + 1..])?;
+            years.checked_mul(12)?.checked_add(months)?
+        }
+        None => digits(unsigned)?,
+    };
+    if negative {
+        months.checked_neg()
+    } else {
+        Some(months)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+
+    fn assert_parses(expected: Value, column_type: Type, text: &str) {
+        let mut scratch = vec![];
+        assert_eq!(
+            Ok(expected),
+            TextFormat::default().parse(&column_type, text, &mut scratch)
+        );
+    }
+
+    fn parse_error(column_type: Type, text: &str) -> Option<ConversionError> {
+        let mut scratch = vec![];
+        TextFormat::default()
+            .parse(&column_type, text, &mut scratch)
+            .err()
+    }
+
+    #[test]
+    fn null() {
+        assert_parses(Value::Null, Type::Integer, "");
+        assert_parses(Value::VarChar("NULL"), Type::VarChar { len: 10 }, "NULL");
+    }
+
+    #[test]
+    fn booleans() {
+        for t in ["t", "TRUE", "yes", "Y", "1", "on"].iter() {
+            assert_parses(Value::Boolean(true), Type::Boolean, t);
+        }
+        for f in ["f", "False", "NO", "n", "0", "off"].iter() {
+            assert_parses(Value::Boolean(false), Type::Boolean, f);
+        }
+        assert_eq!(
+            Some(ConversionError::InvalidText {
+                column_type: String::from("BOOLEAN"),
+                text: String::from("maybe"),
+            }),
+            parse_error(Type::Boolean, "maybe")
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_parses(Value::Integer(-42), Type::Integer, " -42 ");
+        assert!(parse_error(Type::Integer, "4.2").is_some());
+        assert_parses(Value::Float(-1.11), Type::Float, "-1.11");
+        assert_parses(
+            Value::Numeric(Decimal::new(12345, 2)),
+            Type::Numeric {
+                precision: 18,
+                scale: 4,
+            },
+            "123.45",
+        );
+    }
+
+    #[test]
+    fn strings_and_binary() {
+        assert_parses(Value::Char("one"), Type::Char { len: 10 }, "one");
+        assert_parses(Value::VarChar(" ONE "), Type::VarChar { len: 10 }, " ONE ");
+        assert_parses(
+            Value::Binary(&[0xab, 0xcd]),
+            Type::VarBinary { len: 2 },
+            "0xABcd",
+        );
+        assert_parses(Value::Binary(&[0xab]), Type::Binary { len: 1 }, "\\xab");
+        assert!(parse_error(Type::VarBinary { len: 2 }, "abc").is_some());
+        assert!(parse_error(Type::VarBinary { len: 2 }, "zz").is_some());
+    }
+
+    #[test]
+    fn dates_and_times() {
+        assert_parses(
+            Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            Type::Date,
+            "1999-01-08",
+        );
+        let timestamp = NaiveDate::from_ymd_opt(1999, 2, 23)
+            .unwrap()
+            .and_hms_micro_opt(3, 11, 52, 350_000)
+            .unwrap();
+        assert_parses(
+            Value::Timestamp(timestamp),
+            Type::Timestamp,
+            "1999-02-23 03:11:52.35",
+        );
+        assert_parses(
+            Value::Timestamp(timestamp),
+            Type::Timestamp,
+            "1999-02-23T03:11:52.350",
+        );
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        let timestamp_tz =
+            Value::timestamp_tz(&est.with_ymd_and_hms(1999, 1, 8, 7, 4, 37).unwrap());
+        assert_parses(timestamp_tz, Type::TimestampTz, "1999-01-08 07:04:37-05");
+        assert_parses(timestamp_tz, Type::TimestampTz, "1999-01-08T12:04:37Z");
+        assert_parses(
+            Value::Time(NaiveTime::from_hms_opt(7, 9, 23).unwrap()),
+            Type::Time,
+            "07:09:23",
+        );
+        assert_parses(
+            Value::TimeTz(
+                NaiveTime::from_hms_opt(15, 12, 34).unwrap(),
+                FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap(),
+            ),
+            Type::TimeTz,
+            "15:12:34+05:30",
+        );
+        assert!(parse_error(Type::Date, "08/01/1999").is_some());
+    }
+
+    #[test]
+    fn custom_date_format() {
+        let mut scratch = vec![];
+        assert_eq!(
+            Ok(Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap())),
+            TextFormat::default().date_format("%d/%m/%Y").parse(
+                &Type::Date,
+                "08/01/1999",
+                &mut scratch
+            )
+        );
+    }
+
+    #[test]
+    fn intervals() {
+        assert_parses(
+            Value::Interval(Duration::seconds(10_983)),
+            Type::Interval,
+            "03:03:03",
+        );
+        assert_parses(
+            Value::Interval(
+                -(Duration::days(1) + Duration::minutes(90) + Duration::milliseconds(500)),
+            ),
+            Type::Interval,
+            "-1 01:30:00.5",
+        );
+        assert_parses(
+            Value::Interval(Duration::milliseconds(1_500)),
+            Type::Interval,
+            "1.5",
+        );
+        assert!(parse_error(Type::Interval, "1:2:3:4").is_some());
+        assert!(parse_error(Type::Interval, "2562047788015:2562047788015").is_some());
+        assert!(parse_error(Type::Interval, "106751991167 1000000:00").is_some());
+        assert_parses(
+            Value::IntervalYearToMonth(14),
+            Type::IntervalYearToMonth,
+            "1-2",
+        );
+        assert_parses(
+            Value::IntervalYearToMonth(-3),
+            Type::IntervalYearToMonth,
+            "-3",
+        );
+        assert!(parse_error(Type::IntervalYearToMonth, "-0--9223372036854775808").is_some());
+        assert!(parse_error(Type::IntervalYearToMonth, "-1--2").is_some());
+        assert!(parse_error(Type::IntervalYearToMonth, "1-+2").is_some());
+        assert!(parse_error(Type::IntervalYearToMonth, "--3").is_some());
+    }
+}
@@ -0,0 +1,283 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn vnf(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vnf"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vnf-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn convert_inspect_validate() {
+    let dir = temp_dir("convert");
+    let schema = dir.join("schema.sql");
+    let input = dir.join("input.csv");
+    let output = dir.join("output.vnf");
+    let exceptions = dir.join("exceptions.txt");
+    fs::write(
+        &schema,
+        "id INTEGER NOT NULL\nname VARCHAR(20)\njoined DATE\n",
+    )
+    .unwrap();
+    fs::write(&input, "id;name;joined\n1;Fred;08/01/1999\nx;Wilma;\n2;;\n").unwrap();
+
+    let convert = vnf(&[
+        "convert",
+        "--schema",
+        schema.to_str().unwrap(),
+        "--delimiter",
+        ";",
+        "--date-format",
+        "%d/%m/%Y",
+        "--exceptions",
+        exceptions.to_str().unwrap(),
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(convert.status.success());
+    assert_eq!(
+        "2 rows loaded, 1 rejected\n",
+        String::from_utf8_lossy(&convert.stderr)
+    );
+    assert!(fs::read_to_string(&exceptions)
+        .unwrap()
+        .starts_with("line 3: column `id` (index 0):"));
+
+    let inspect = vnf(&[
+        "inspect",
+        "--schema",
+        schema.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(inspect.status.success());
+    assert_eq!(
+        "3 columns\n\
+         \x20 #0 id INTEGER (8 bytes)\n\
+         \x20 #1 name VARCHAR(20) (variable)\n\
+         \x20 #2 joined DATE (8 bytes)\n\
+         row 0: id=1, name='Fred', joined='1999-01-08'\n\
+         row 1: id=2, name=NULL, joined=NULL\n",
+        String::from_utf8_lossy(&inspect.stdout)
+    );
+
+    let validate = vnf(&["validate", output.to_str().unwrap()]);
+    assert!(validate.status.success());
+    assert_eq!("2 rows OK\n", String::from_utf8_lossy(&validate.stdout));
+
+    fs::write(&schema, "id INTEGER\nname VARCHAR\n").unwrap();
+    let validate = vnf(&[
+        "validate",
+        "--schema",
+        schema.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(!validate.status.success());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn convert_json() {
+    let dir = temp_dir("json");
+    let schema = dir.join("schema.sql");
+    let input = dir.join("input.jsonl");
+    let output = dir.join("output.vnf");
+    let rejected = dir.join("rejected.jsonl");
+    fs::write(
+        &schema,
+        "id INTEGER NOT NULL\nname VARCHAR\nseen TIMESTAMP\n",
+    )
+    .unwrap();
+    fs::write(
+        &input,
+        "{\"id\": 1, \"user\": {\"name\": \"Fred\"}, \"seen\": \"1999-01-08T11:04:37\"}\n\
+         {\"id\": null}\n\
+         {\"id\": 2}\n",
+    )
+    .unwrap();
+
+    let convert = vnf(&[
+        "convert",
+        "--format",
+        "json",
+        "--schema",
+        schema.to_str().unwrap(),
+        "--path",
+        "name=/user/name",
+        "--rejected-data",
+        rejected.to_str().unwrap(),
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(convert.status.success());
+    assert_eq!(
+        "2 rows loaded, 1 rejected\n",
+        String::from_utf8_lossy(&convert.stderr)
+    );
+    assert_eq!("{\"id\": null}\n", fs::read_to_string(&rejected).unwrap());
+
+    let inspect = vnf(&[
+        "inspect",
+        "--schema",
+        schema.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(inspect.status.success());
+    assert!(String::from_utf8_lossy(&inspect.stdout).ends_with(
+        "row 0: id=1, name='Fred', seen='1999-01-08 11:04:37'\n\
+         row 1: id=2, name=NULL, seen=NULL\n"
+    ));
+
+    let convert = vnf(&[
+        "convert",
+        "--format",
+        "json",
+        "--schema",
+        schema.to_str().unwrap(),
+        "--path",
+        "missing=/x",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(!convert.status.success());
+    assert_eq!(
+        "vnf: no column named `missing`\n",
+        String::from_utf8_lossy(&convert.stderr)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn convert_parquet() {
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    let dir = temp_dir("parquet");
+    let input = dir.join("input.parquet");
+    let output = dir.join("output.vnf");
+    let batch = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+        (
+            "name",
+            Arc::new(StringArray::from(vec![Some("Fred"), None])) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let mut writer = parquet::arrow::ArrowWriter::try_new(
+        fs::File::create(&input).unwrap(),
+        batch.schema(),
+        None,
+    )
+    .unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let convert = vnf(&[
+        "convert",
+        "--format",
+        "parquet",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(convert.status.success());
+    assert_eq!(
+        "2 rows loaded, 0 rejected\n",
+        String::from_utf8_lossy(&convert.stderr)
+    );
+
+    let schema = dir.join("schema.sql");
+    fs::write(&schema, "name VARCHAR\n").unwrap();
+    let inspect = vnf(&[
+        "inspect",
+        "--schema",
+        schema.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(!inspect.status.success());
+
+    let convert = vnf(&[
+        "convert",
+        "--format",
+        "parquet",
+        "--schema",
+        schema.to_str().unwrap(),
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(convert.status.success());
+    let inspect = vnf(&[
+        "inspect",
+        "--schema",
+        schema.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(inspect.status.success());
+    assert!(String::from_utf8_lossy(&inspect.stdout)
+        .ends_with("row 0: name='Fred'\nrow 1: name=NULL\n"));
+
+    let convert = vnf(&[
+        "convert",
+        "--format",
+        "parquet",
+        "--abort-on-error",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]);
+    assert!(!convert.status.success());
+    assert!(String::from_utf8_lossy(&convert.stderr).contains("any error is fatal"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn ddl() {
+    let dir = temp_dir("ddl");
+    let schema = dir.join("schema.sql");
+    fs::write(&schema, "id INTEGER NOT NULL\nname VARCHAR\n").unwrap();
+    let ddl = vnf(&[
+        "ddl",
+        "--schema",
+        schema.to_str().unwrap(),
+        "--segmented-by",
+        "id",
+        "customers",
+    ]);
+    assert!(ddl.status.success());
+    assert_eq!(
+        "CREATE TABLE customers (\n\
+         \x20   \"id\" INTEGER NOT NULL,\n\
+         \x20   \"name\" VARCHAR(65000)\n\
+         )\n\
+         SEGMENTED BY HASH(\"id\") ALL NODES;\n",
+        String::from_utf8_lossy(&ddl.stdout)
+    );
+
+    let ddl = vnf(&[
+        "ddl",
+        "--schema",
+        schema.to_str().unwrap(),
+        "--order-by",
+        "missing",
+        "customers",
+    ]);
+    assert!(!ddl.status.success());
+    assert_eq!(
+        "vnf: no column named `missing` in the schema\n",
+        String::from_utf8_lossy(&ddl.stderr)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
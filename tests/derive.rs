@@ -0,0 +1,102 @@
+#![cfg(feature = "derive")]
+
+use chrono::NaiveDate;
+use vnf::column::{Type, Value};
+use vnf::numeric::Decimal;
+use vnf::{Column, Schema, VnfRow, VnfWriter};
+
+#[derive(VnfRow)]
+struct Customer<'a> {
+    id: i64,
+    #[vnf(char(10))]
+    code: &'a str,
+    #[vnf(varchar(20))]
+    name: Option<String>,
+    #[vnf(rename = "joined_on")]
+    joined: NaiveDate,
+    #[vnf(numeric(18, 4))]
+    balance: Option<Decimal>,
+    #[vnf(binary(2))]
+    flags: Vec<u8>,
+    #[vnf(skip)]
+    #[allow(dead_code)]
+    cache: std::collections::HashMap<String, String>,
+}
+
+fn customer() -> Customer<'static> {
+    Customer {
+        id: 7,
+        code: "FRED",
+        name: None,
+        joined: NaiveDate::from_ymd_opt(1999, 1, 8).unwrap(),
+        balance: Some(Decimal::new(12345, 2)),
+        flags: vec![0xab, 0xcd],
+        cache: Default::default(),
+    }
+}
+
+#[test]
+fn derived_schema() {
+    assert_eq!(
+        Schema::new(vec![
+            Column::new("id", Type::Integer, false),
+            Column::new("code", Type::Char { len: 10 }, false),
+            Column::new("name", Type::VarChar { len: 20 }, true),
+            Column::new("joined_on", Type::Date, false),
+            Column::new(
+                "balance",
+                Type::Numeric {
+                    precision: 18,
+                    scale: 4
+                },
+                true
+            ),
+            Column::new("flags", Type::Binary { len: 2 }, false),
+        ]),
+        Customer::schema()
+    );
+}
+
+#[test]
+fn write_record() {
+    let customer = customer();
+    let mut writer = VnfWriter::new(vec![], Customer::schema());
+    writer.write_record(&customer).unwrap();
+
+    let mut expected = VnfWriter::new(vec![], Customer::schema());
+    expected
+        .write_row(&[
+            Value::Integer(7),
+            Value::VarChar("FRED"),
+            Value::Null,
+            Value::Date(customer.joined),
+            Value::Numeric(Decimal::new(12345, 2)),
+            Value::Binary(&[0xab, 0xcd]),
+        ])
+        .unwrap();
+    assert_eq!(expected.finish().unwrap(), writer.finish().unwrap());
+}
+
+#[test]
+fn write_record_conversion_error() {
+    let mut customer = customer();
+    customer.balance = Some(Decimal::new(10_i128.pow(15), 0));
+    let mut writer = VnfWriter::new(vec![], Customer::schema());
+    assert!(matches!(
+        writer.write_record(&customer),
+        Err(vnf::Error::Conversion { index: 4, .. })
+    ));
+    assert_eq!(0, writer.bytes_written());
+}
+
+#[test]
+fn write_record_schema_mismatch() {
+    let mut schema = Customer::schema().columns().to_vec();
+    schema.swap(0, 3);
+    let mut writer = VnfWriter::new(vec![], Schema::new(schema));
+    assert!(matches!(
+        writer.write_record(&customer()),
+        Err(vnf::Error::SchemaMismatch)
+    ));
+    assert_eq!(0, writer.bytes_written());
+}
@@ -1,42 +1,48 @@
-use chrono::{NaiveDate, NaiveTime, Timelike};
-use vnf::column::Type;
-use vnf::date::{VerticaDate, _timetz};
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use std::io::Cursor;
+use vnf::column::{Type, Value};
 use vnf::header::{FILLER, SIGNATURE, VERSION};
+use vnf::numeric::Decimal;
+use vnf::schema::Schema;
+use vnf::{VnfReader, VnfWriter};
+
+#[rustfmt::skip]
+const EXAMPLE: [u8; 197] = [
+    0x4E, 0x41, 0x54, 0x49, 0x56, 0x45, 0x0A, 0xFF, 0x0D, 0x0A, 0x00, 0x3D, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x0E, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x0A, 0x00,
+    0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08,
+    0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x03, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
+    0x00, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xC3, 0xF5, 0x28, 0x5C, 0x8F, 0xC2, 0xF1, 0xBF, 0x6F, 0x6E, 0x65, 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x20, 0x20, 0x03, 0x00, 0x00, 0x00, 0x4F, 0x4E, 0x45, 0x01, 0x9A, 0xFE, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x30, 0x85, 0xB3, 0x4F, 0x7E, 0xE7, 0xFF, 0xFF, 0x40, 0x1F, 0x3E,
+    0x64, 0xE8, 0xE3, 0xFF, 0xFF, 0xC0, 0x2E, 0x98, 0xFF, 0x05, 0x00, 0x00, 0x00, 0xD0, 0x97,
+    0x01, 0x80, 0xF0, 0x79, 0xF0, 0x10, 0x02, 0x00, 0x00, 0x00, 0xAB, 0xCD, 0xAB, 0xCD, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x64, 0xD6, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x47, 0xA3, 0x8E, 0x02, 0x00,
+    0x00, 0x00,
+];
 
 #[test]
 fn example() {
-    let expected = vec![
-        0x4E, 0x41, 0x54, 0x49, 0x56, 0x45, 0x0A, 0xFF, 0x0D, 0x0A, 0x00, 0x3D, 0x00, 0x00, 0x00,
-        0x01, 0x00, 0x00, 0x0E, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x0A, 0x00,
-        0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08,
-        0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
-        0xFF, 0xFF, 0xFF, 0xFF, 0x03, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
-        0x00, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0xC3, 0xF5, 0x28, 0x5C, 0x8F, 0xC2, 0xF1, 0xBF, 0x6F, 0x6E, 0x65, 0x20, 0x20, 0x20, 0x20,
-        0x20, 0x20, 0x20, 0x03, 0x00, 0x00, 0x00, 0x4F, 0x4E, 0x45, 0x01, 0x9A, 0xFE, 0xFF, 0xFF,
-        0xFF, 0xFF, 0xFF, 0xFF, 0x30, 0x85, 0xB3, 0x4F, 0x7E, 0xE7, 0xFF, 0xFF, 0x40, 0x1F, 0x3E,
-        0x64, 0xE8, 0xE3, 0xFF, 0xFF, 0xC0, 0x2E, 0x98, 0xFF, 0x05, 0x00, 0x00, 0x00, 0xD0, 0x97,
-        0x01, 0x80, 0xF0, 0x79, 0xF0, 0x10, 0x02, 0x00, 0x00, 0x00, 0xAB, 0xCD, 0xAB, 0xCD, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x64, 0xD6, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x47, 0xA3, 0x8E, 0x02, 0x00,
-        0x00, 0x00,
-    ];
+    let expected = EXAMPLE;
     let cols = vec![
         Type::Integer,
         Type::Float,
         Type::Char { len: 10 },
-        Type::VarChar,
+        Type::VarChar { len: 20 },
         Type::Boolean,
         Type::Date,
         Type::Timestamp,
         Type::TimestampTz,
         Type::Time,
         Type::TimeTz,
-        Type::VarBinary,
+        Type::VarBinary { len: 20 },
         Type::Binary { len: 3 },
         Type::Numeric {
             precision: 38,
-            _scale: 0,
+            scale: 0,
         },
         Type::Interval,
     ];
@@ -65,40 +71,225 @@ fn example() {
     assert_eq!(&expected[98..108], "one       ".as_bytes()); // Char(10)
     assert_eq!(expected[108..112], ("ONE".len() as u32).to_le_bytes()); // Number of bytes in following VarChar
     assert_eq!(&expected[112..115], "ONE".as_bytes()); // Var Char
+    let mut varchar = Vec::<u8>::new();
+    Type::VarChar { len: 20 }
+        .append(&mut varchar, &Value::VarChar("ONE"))
+        .unwrap();
+    assert_eq!(&expected[108..115], varchar.as_slice()); // VarChar - ONE
     assert_eq!(&expected[115..116], &[1u8]); // Boolean
-    assert_eq!(
-        &expected[116..124],
-        NaiveDate::from_ymd(1999, 1, 8)
-            .to_y2k_epoch_duration()
-            .num_days()
-            .to_le_bytes()
-    ); // Date - 1999-01-08
+    let mut date = Vec::<u8>::new();
+    Type::Date
+        .append(
+            &mut date,
+            &Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+        )
+        .unwrap();
+    assert_eq!(&expected[116..124], date.as_slice()); // Date - 1999-01-08
 
-    assert_eq!(
-        &expected[124..132],
-        NaiveDate::from_ymd(1999, 2, 23)
-            .and_hms_micro(3, 11, 52, 350_000)
-            .to_y2k_epoch_duration()
-            .num_microseconds()
-            .unwrap()
-            .to_le_bytes()
-    ); // TIMESTAMP - 1999-02-23 03:11:52.35
+    let mut timestamp = Vec::<u8>::new();
+    Type::Timestamp
+        .append(
+            &mut timestamp,
+            &Value::Timestamp(
+                NaiveDate::from_ymd_opt(1999, 2, 23)
+                    .unwrap()
+                    .and_hms_micro_opt(3, 11, 52, 350_000)
+                    .unwrap(),
+            ),
+        )
+        .unwrap();
+    assert_eq!(&expected[124..132], timestamp.as_slice()); // TIMESTAMP - 1999-02-23 03:11:52.35
 
-    assert_eq!(
-        &expected[132..140],
-        NaiveDate::from_ymd(1999, 1, 8)
-            .and_hms(12, 4, 37)
-            .to_y2k_epoch_duration()
-            .num_microseconds()
-            .unwrap()
-            .to_le_bytes()
-    ); // TIMESTAMPTZ - 1999-01-08 07:04:37-05
+    let mut timestamp_tz = Vec::<u8>::new();
+    Type::TimestampTz
+        .append(
+            &mut timestamp_tz,
+            &Value::timestamp_tz(
+                &FixedOffset::west_opt(5 * 3600)
+                    .unwrap()
+                    .with_ymd_and_hms(1999, 1, 8, 7, 4, 37)
+                    .unwrap(),
+            ),
+        )
+        .unwrap();
+    assert_eq!(&expected[132..140], timestamp_tz.as_slice()); // TIMESTAMPTZ - 1999-01-08 07:04:37-05
+
+    let mut time = Vec::<u8>::new();
+    Type::Time
+        .append(
+            &mut time,
+            &Value::Time(NaiveTime::from_hms_opt(7, 9, 23).unwrap()),
+        )
+        .unwrap();
+    assert_eq!(&expected[140..148], time.as_slice()); // TIME - 07:09:23
+
+    let mut time_tz = Vec::<u8>::new();
+    Type::TimeTz
+        .append(
+            &mut time_tz,
+            &Value::TimeTz(
+                NaiveTime::from_hms_opt(15, 12, 34).unwrap(),
+                FixedOffset::west_opt(5 * 3600).unwrap(),
+            ),
+        )
+        .unwrap();
+    assert_eq!(&expected[148..156], time_tz.as_slice()); // TIMETZ - 15:12:34-05
+
+    let mut binary = Vec::<u8>::new();
+    Type::VarBinary { len: 20 }
+        .append(&mut binary, &Value::Binary(&[0xAB, 0xCD]))
+        .unwrap();
+    Type::Binary { len: 3 }
+        .append(&mut binary, &Value::Binary(&[0xAB, 0xCD]))
+        .unwrap();
+    assert_eq!(&expected[156..165], binary.as_slice()); // VARBINARY - AB CD, BINARY(3) - AB CD 00
+
+    let mut numeric = Vec::<u8>::new();
+    Type::Numeric {
+        precision: 38,
+        scale: 0,
+    }
+    .append(&mut numeric, &Value::Numeric(Decimal::new(1_234_532, 0)))
+    .unwrap();
+    assert_eq!(&expected[165..189], numeric.as_slice()); // NUMERIC(38,0) - 1234532
+
+    let mut interval = Vec::<u8>::new();
+    Type::Interval
+        .append(
+            &mut interval,
+            &Value::Interval(Duration::hours(3) + Duration::minutes(3) + Duration::seconds(3)),
+        )
+        .unwrap();
+    assert_eq!(&expected[189..197], interval.as_slice()); // INTERVAL - 03:03:03
+}
+
+#[rustfmt::skip]
+#[test]
+fn date_row() {
+    let mut writer = VnfWriter::new(Cursor::new(vec![]), &[Type::Date]);
+    writer
+        .write_row(&[Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap())])
+        .unwrap();
+    let out = writer.finish().unwrap();
 
     assert_eq!(
-        &expected[140..148],
-        (NaiveTime::from_hms(7, 9, 23).num_seconds_from_midnight() as u64 * 1_000_000u64)
-            .to_le_bytes()
-    ); // TIME - 07:09:23
+        &vec![0x4E, 0x41, 0x54, 0x49, 0x56, 0x45, 0x0A, 0xFF, 0x0D, 0x0A, 0x00, // SIGNATURE
+              0x09, 0x00, 0x00, 0x00, // header_area_length
+              0x01, 0x00, // VERSION
+              0x00, // FILLER
+              0x01, 0x00, // number_of_columns
+              0x08, 0x00, 0x00, 0x00, // size of column 1
+              0x08, 0x00, 0x00, 0x00, // size of row 1
+              0x00, // null value bit field
+              0x9A, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // DATE - 1999-01-08
+        ],
+        out.get_ref()
+    );
+}
+
+#[test]
+fn write_example() {
+    let mut writer = VnfWriter::new(
+        Cursor::new(vec![]),
+        &[
+            Type::Integer,
+            Type::Float,
+            Type::Char { len: 10 },
+            Type::VarChar { len: 20 },
+            Type::Boolean,
+            Type::Date,
+            Type::Timestamp,
+            Type::TimestampTz,
+            Type::Time,
+            Type::TimeTz,
+            Type::VarBinary { len: 20 },
+            Type::Binary { len: 3 },
+            Type::Numeric {
+                precision: 38,
+                scale: 0,
+            },
+            Type::Interval,
+        ],
+    );
+    let est = FixedOffset::west_opt(5 * 3600).unwrap();
+    writer
+        .write_row(&[
+            Value::Integer(1),
+            Value::Float(-1.11),
+            Value::Char("one"),
+            Value::VarChar("ONE"),
+            Value::Boolean(true),
+            Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            Value::Timestamp(
+                NaiveDate::from_ymd_opt(1999, 2, 23)
+                    .unwrap()
+                    .and_hms_micro_opt(3, 11, 52, 350_000)
+                    .unwrap(),
+            ),
+            Value::timestamp_tz(&est.with_ymd_and_hms(1999, 1, 8, 7, 4, 37).unwrap()),
+            Value::Time(NaiveTime::from_hms_opt(7, 9, 23).unwrap()),
+            Value::TimeTz(NaiveTime::from_hms_opt(15, 12, 34).unwrap(), est),
+            Value::Binary(&[0xAB, 0xCD]),
+            Value::Binary(&[0xAB, 0xCD]),
+            Value::Numeric(Decimal::new(1_234_532, 0)),
+            Value::Interval(Duration::seconds(3 * 3600 + 3 * 60 + 3)),
+        ])
+        .unwrap();
+
+    assert_eq!(&EXAMPLE[..], writer.finish().unwrap().get_ref().as_slice());
+}
 
-    assert_eq!(_timetz(15, 12, 34, -5).to_le_bytes(), &expected[148..156]); // TIMETZ - 15:12:34-05
+#[test]
+fn read_example() {
+    let types = [
+        Type::Integer,
+        Type::Float,
+        Type::Char { len: 10 },
+        Type::VarChar { len: 20 },
+        Type::Boolean,
+        Type::Date,
+        Type::Timestamp,
+        Type::TimestampTz,
+        Type::Time,
+        Type::TimeTz,
+        Type::VarBinary { len: 20 },
+        Type::Binary { len: 3 },
+        Type::Numeric {
+            precision: 38,
+            scale: 0,
+        },
+        Type::Interval,
+    ];
+    let schema = Schema::from(&types);
+    let mut reader = VnfReader::new(&EXAMPLE[..]).unwrap();
+    reader.check_schema(&schema).unwrap();
+
+    let row = reader.read_row().unwrap().unwrap();
+    assert_eq!(115, row.data_length());
+    let est = FixedOffset::west_opt(5 * 3600).unwrap();
+    assert_eq!(
+        vec![
+            Value::Integer(1),
+            Value::Float(-1.11),
+            Value::Char("one       "),
+            Value::VarChar("ONE"),
+            Value::Boolean(true),
+            Value::Date(NaiveDate::from_ymd_opt(1999, 1, 8).unwrap()),
+            Value::Timestamp(
+                NaiveDate::from_ymd_opt(1999, 2, 23)
+                    .unwrap()
+                    .and_hms_micro_opt(3, 11, 52, 350_000)
+                    .unwrap(),
+            ),
+            Value::timestamp_tz(&est.with_ymd_and_hms(1999, 1, 8, 7, 4, 37).unwrap()),
+            Value::Time(NaiveTime::from_hms_opt(7, 9, 23).unwrap()),
+            Value::TimeTz(NaiveTime::from_hms_opt(15, 12, 34).unwrap(), est),
+            Value::Binary(&[0xAB, 0xCD]),
+            Value::Binary(&[0xAB, 0xCD, 0x00]),
+            Value::Numeric(Decimal::new(1_234_532, 0)),
+            Value::Interval(Duration::seconds(3 * 3600 + 3 * 60 + 3)),
+        ],
+        row.values(&schema).unwrap()
+    );
+    assert!(reader.read_row().unwrap().is_none());
 }
@@ -0,0 +1,270 @@
+//! `#[derive(VnfRow)]` for the `vnf` crate, enabled by its `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{
+    parenthesized, parse_macro_input, token, Data, DeriveInput, Error, Fields, LitInt, LitStr,
+    Result, Token,
+};
+
+/// Implements `vnf::VnfRow` for a struct with named fields. See the trait
+/// for the `#[vnf(...)]` field attributes.
+#[proc_macro_derive(VnfRow, attributes(vnf))]
+pub fn derive_vnf_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Column type set by a field attribute.
+enum ColumnType {
+    Char(u32),
+    VarChar(Option<u32>),
+    Binary(u32),
+    VarBinary(Option<u32>),
+    Numeric(u32, u32),
+}
+
+#[derive(Default)]
+struct FieldAttributes {
+    column_type: Option<ColumnType>,
+    rename: Option<String>,
+    skip: bool,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "VnfRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "VnfRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns = vec![];
+    let mut pushes = vec![];
+    for field in fields {
+        let attributes = field_attributes(field)?;
+        if attributes.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let name = attributes
+            .rename
+            .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+
+        // Naming the marker trait makes a mismatched attribute a compile error
+        let column_type = match attributes.column_type {
+            None => quote!(<#ty as ::vnf::record::DefaultType>::TYPE),
+            Some(ColumnType::Char(len)) => {
+                let len = len as usize;
+                checked(ty, quote!(TextField), quote!(Char { len: #len }))
+            }
+            Some(ColumnType::VarChar(len)) => {
+                let len = var_len(len);
+                checked(ty, quote!(TextField), quote!(VarChar { len: #len }))
+            }
+            Some(ColumnType::Binary(len)) => {
+                let len = len as usize;
+                checked(ty, quote!(BinaryField), quote!(Binary { len: #len }))
+            }
+            Some(ColumnType::VarBinary(len)) => {
+                let len = var_len(len);
+                checked(ty, quote!(BinaryField), quote!(VarBinary { len: #len }))
+            }
+            Some(ColumnType::Numeric(precision, scale)) => checked(
+                ty,
+                quote!(NumericField),
+                quote!(Numeric {
+                    precision: #precision,
+                    scale: #scale
+                }),
+            ),
+        };
+        columns.push(quote! {
+            ::vnf::Column::new(
+                #name,
+                #column_type,
+                <#ty as ::vnf::record::Field>::NULLABLE,
+            )
+        });
+        pushes.push(quote! {
+            row.push(&::vnf::record::Field::to_value(&self.#ident))?;
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::vnf::record::VnfRow for #ident #ty_generics #where_clause {
+            fn schema() -> ::vnf::Schema {
+                ::vnf::Schema::new(::std::vec![#(#columns),*])
+            }
+
+            fn push_columns(&self, row: &mut ::vnf::RowBuilder) -> ::vnf::Result<()> {
+                #(#pushes)*
+                Ok(())
+            }
+        }
+    })
+}
+
+fn checked(ty: &syn::Type, marker: TokenStream2, column_type: TokenStream2) -> TokenStream2 {
+    quote! {{
+        fn check<T: ::vnf::record::#marker + ?Sized>() {}
+        check::<#ty>();
+        ::vnf::column::Type::#column_type
+    }}
+}
+
+fn field_attributes(field: &syn::Field) -> Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("vnf")) {
+        attr.parse_nested_meta(|meta| {
+            let column_type = if meta.path.is_ident("skip") {
+                attributes.skip = true;
+                return Ok(());
+            } else if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                attributes.rename = Some(name.value());
+                return Ok(());
+            } else if meta.path.is_ident("char") {
+                ColumnType::Char(length(&meta, "CHAR")?)
+            } else if meta.path.is_ident("varchar") {
+                ColumnType::VarChar(optional_length(&meta, "VARCHAR")?)
+            } else if meta.path.is_ident("binary") {
+                ColumnType::Binary(length(&meta, "BINARY")?)
+            } else if meta.path.is_ident("varbinary") {
+                ColumnType::VarBinary(optional_length(&meta, "VARBINARY")?)
+            } else if meta.path.is_ident("numeric") {
+                numeric(&meta)?
+            } else {
+                return Err(meta.error("unknown vnf attribute"));
+            };
+            if attributes.column_type.is_some() {
+                return Err(meta.error("column type is already set"));
+            }
+            attributes.column_type = Some(column_type);
+            Ok(())
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// Longest CHAR, VARCHAR, BINARY or VARBINARY, `vnf::column::MAX_VAR_LEN`.
+const MAX_LEN: u32 = 65_000;
+
+/// The `(n)` of `char(n)` and `binary(n)`.
+fn length(meta: &ParseNestedMeta, name: &str) -> Result<u32> {
+    let content;
+    parenthesized!(content in meta.input);
+    let len: LitInt = content.parse()?;
+    match len.base10_parse()? {
+        0 => Err(Error::new(
+            len.span(),
+            format!("{} length must be at least 1", name),
+        )),
+        n if n > MAX_LEN => Err(Error::new(
+            len.span(),
+            format!("{} length must be at most {}", name, MAX_LEN),
+        )),
+        len => Ok(len),
+    }
+}
+
+/// The `(n)` of `varchar(n)` and `varbinary(n)`, which may be left out.
+fn optional_length(meta: &ParseNestedMeta, name: &str) -> Result<Option<u32>> {
+    if meta.input.peek(token::Paren) {
+        length(meta, name).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// A VARCHAR or VARBINARY length, `MAX_VAR_LEN` if none was given.
+fn var_len(len: Option<u32>) -> TokenStream2 {
+    match len {
+        Some(len) => {
+            let len = len as usize;
+            quote!(#len)
+        }
+        None => quote!(::vnf::column::MAX_VAR_LEN),
+    }
+}
+
+/// The `(precision, scale)` or `(precision)` of `numeric`.
+fn numeric(meta: &ParseNestedMeta) -> Result<ColumnType> {
+    let content;
+    parenthesized!(content in meta.input);
+    let precision: LitInt = content.parse()?;
+    let scale = if content.is_empty() {
+        0
+    } else {
+        content.parse::<Token![,]>()?;
+        content.parse::<LitInt>()?.base10_parse()?
+    };
+    match precision.base10_parse()? {
+        0 => Err(Error::new(
+            precision.span(),
+            "NUMERIC precision must be at least 1",
+        )),
+        p if scale > p => Err(Error::new(
+            precision.span(),
+            "NUMERIC scale must not exceed the precision",
+        )),
+        p => Ok(ColumnType::Numeric(p, scale)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn error(input: DeriveInput) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn lengths() {
+        assert!(expand(parse_quote! {
+            struct Row {
+                #[vnf(varchar(65000))]
+                name: String,
+            }
+        })
+        .is_ok());
+        assert_eq!(
+            "VARCHAR length must be at most 65000",
+            error(parse_quote! {
+                struct Row {
+                    #[vnf(varchar(65001))]
+                    name: String,
+                }
+            })
+        );
+        assert_eq!(
+            "BINARY length must be at least 1",
+            error(parse_quote! {
+                struct Row {
+                    #[vnf(binary(0))]
+                    raw: Vec<u8>,
+                }
+            })
+        );
+    }
+}